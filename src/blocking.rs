@@ -0,0 +1,50 @@
+use core::{
+    pin::Pin,
+    task::{Context, Waker},
+};
+use std::{
+    sync::Arc,
+    task::Wake,
+    thread::{self, Thread},
+    time::Instant,
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    #[inline]
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    #[inline]
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Parks the current thread until `fut` is ready or `deadline` is reached.
+///
+/// Returns `true` if `fut` is ready.
+pub(crate) fn park_on<F: Future<Output = ()> + Unpin>(
+    fut: &mut F,
+    deadline: Option<Instant>,
+) -> bool {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if Pin::new(&mut *fut).poll(&mut cx).is_ready() {
+            return true;
+        }
+        match deadline {
+            None => thread::park(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return false;
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+    }
+}
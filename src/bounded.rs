@@ -0,0 +1,454 @@
+use core::{
+    fmt,
+    mem::ManuallyDrop,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use alloc::collections::VecDeque;
+
+use derive_more::Debug;
+
+use crate::{
+    group::{Wait, private::Waitable},
+    layout::ReportLayout,
+    sync::{WaitGroupLayoutExt, WaitGroupWrapper},
+    twin_ref::{ClonableTwinRef, TwinRef},
+};
+
+/// Queue of acquirers waiting for room.
+///
+/// The weight of every token is counted in the reference count of the layout,
+/// besides the one of the factory. The count is only changed under the lock
+/// of the queue, so that a token is either admitted or queued atomically.
+#[derive(Debug)]
+struct Queue {
+    capacity: usize,
+    next_id: usize,
+    /// Acquirers in the order they were queued. Admitted acquirers keep their
+    /// place until they are polled or dropped.
+    acquirers: VecDeque<Acquirer>,
+}
+
+#[derive(Debug)]
+struct Acquirer {
+    id: usize,
+    weight: usize,
+    waker: Waker,
+    state: AcquirerState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AcquirerState {
+    Queued,
+    /// Admitted, with its weight counted.
+    Admitted,
+    /// Admitted and woken.
+    Woken,
+}
+
+type BoundedLayout = ReportLayout<Queue>;
+
+impl Queue {
+    /// Total weight of the outstanding tokens.
+    #[inline]
+    fn weight(twin: &ClonableTwinRef<BoundedLayout>) -> usize {
+        // Not counting the factory
+        twin.outstanding() - 1
+    }
+
+    #[inline]
+    fn is_waiting(&self) -> bool {
+        self.acquirers
+            .iter()
+            .any(|acquirer| acquirer.state == AcquirerState::Queued)
+    }
+
+    #[inline]
+    fn position(&self, id: usize) -> usize {
+        self.acquirers
+            .iter()
+            .position(|acquirer| acquirer.id == id)
+            .expect("queued acquirer")
+    }
+
+    /// Admits the queued acquirers in order, until one does not fit.
+    ///
+    /// Returns the waker of the first admitted acquirer not yet woken. Each
+    /// admitted acquirer wakes the next one once polled or dropped, so that
+    /// the wakers are not collected.
+    fn admit(&mut self, twin: &ClonableTwinRef<BoundedLayout>) -> Option<Waker> {
+        if self.acquirers.is_empty() {
+            return None;
+        }
+        let mut weight = Self::weight(twin);
+        for acquirer in &mut self.acquirers {
+            if acquirer.state != AcquirerState::Queued {
+                continue;
+            }
+            if self.capacity - weight < acquirer.weight {
+                break;
+            }
+            twin.count_many(acquirer.weight);
+            weight += acquirer.weight;
+            acquirer.state = AcquirerState::Admitted;
+        }
+        let acquirer = self
+            .acquirers
+            .iter_mut()
+            .find(|acquirer| acquirer.state == AcquirerState::Admitted)?;
+        acquirer.state = AcquirerState::Woken;
+        Some(acquirer.waker.clone())
+    }
+}
+
+/// Releases `weight` clones of `twin` under the lock of the queue.
+///
+/// The layout may be freed once the last clone is released, so the lock is
+/// released before the last twin is dropped.
+fn release(twin: &ClonableTwinRef<BoundedLayout>, weight: usize) {
+    let (last, waker) = twin.report().with(|queue| {
+        let last = unsafe { twin.release_many(weight) };
+        let waker = if last.is_none() {
+            queue.admit(twin)
+        } else {
+            None
+        };
+        (last, waker)
+    });
+    drop(last);
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Wait group with a bounded total weight of outstanding tokens.
+///
+/// Tokens are acquired asynchronously with
+/// [`BoundedGroupTokenFactory::acquire`], which waits until the total weight
+/// of outstanding [`BoundedGroupToken`]s leaves room for the new one.
+///
+/// Acquirers are admitted in FIFO order: once an acquirer has to wait, later
+/// ones queue behind it even if they would fit, so a heavy acquirer is never
+/// starved by lighter ones. Releasing a token only wakes the acquirers it
+/// makes room for.
+///
+/// The group completes once the factory and every token have been dropped.
+///
+/// ```rust
+/// # use compact_waitgroup::BoundedWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (wg, factory) = BoundedWaitGroup::new(4);
+///
+/// let token_a = factory.acquire(3).await;
+/// assert!(factory.try_acquire(2).is_none());
+///
+/// token_a.release();
+/// let token_b = factory.acquire(2).await;
+///
+/// factory.release();
+/// token_b.release();
+/// wg.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct BoundedWaitGroup(
+    #[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef<BoundedLayout>>,
+);
+
+/// Factory of [`BoundedGroupToken`].
+///
+/// Keeps the [`BoundedWaitGroup`] from completing until released, without
+/// counting towards its capacity.
+#[must_use]
+pub struct BoundedGroupTokenFactory(ManuallyDrop<ClonableTwinRef<BoundedLayout>>);
+
+/// Weighted group token of a [`BoundedWaitGroup`].
+#[must_use]
+pub struct BoundedGroupToken {
+    token: ManuallyDrop<ClonableTwinRef<BoundedLayout>>,
+    weight: usize,
+}
+
+/// Future that acquires a [`BoundedGroupToken`].
+///
+/// Created by [`BoundedGroupTokenFactory::acquire`].
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe. Dropping it gives up its place in the
+/// queue, or the room it was admitted to.
+///
+/// # Panics
+///
+/// Polling the future again after it has completed panics.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct Acquire<'a> {
+    factory: Option<&'a BoundedGroupTokenFactory>,
+    weight: usize,
+    #[debug(skip)]
+    id: Option<usize>,
+}
+
+impl BoundedWaitGroup {
+    /// Creates a new `BoundedWaitGroup` with the given capacity and a
+    /// [`BoundedGroupTokenFactory`].
+    pub fn new(capacity: usize) -> (Self, BoundedGroupTokenFactory) {
+        let inner = ReportLayout::new(Queue {
+            capacity,
+            next_id: 0,
+            acquirers: VecDeque::new(),
+        });
+        let (wg, token) = TwinRef::new_clonable(inner);
+        (
+            Self(WaitGroupWrapper::new(wg)),
+            BoundedGroupTokenFactory(ManuallyDrop::new(token)),
+        )
+    }
+
+    /// Checks if the `BoundedWaitGroup` has completed.
+    ///
+    /// This returns `true` if the [`BoundedGroupTokenFactory`] and all
+    /// [`BoundedGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+
+    /// Polls the `BoundedWaitGroup` for completion through a shared
+    /// reference.
+    ///
+    /// Registers the waker as
+    /// [`WaitGroup::poll_done`](crate::WaitGroup::poll_done) does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_shared(cx, None)
+    }
+
+    /// Returns a future that waits for the `BoundedWaitGroup` through a
+    /// shared reference.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+}
+
+impl BoundedGroupTokenFactory {
+    /// Consumes the factory.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns the capacity of the group.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.report().with(|queue| queue.capacity)
+    }
+
+    /// Returns the total weight of the outstanding [`BoundedGroupToken`]s.
+    #[inline]
+    pub fn weight(&self) -> usize {
+        Queue::weight(&self.0)
+    }
+
+    /// Acquires a token of the given weight, waiting for enough capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is zero or exceeds the capacity.
+    #[inline]
+    pub fn acquire(&self, weight: usize) -> Acquire<'_> {
+        assert!(weight != 0, "weight must be nonzero");
+        assert!(weight <= self.capacity(), "weight exceeds capacity");
+        Acquire {
+            factory: Some(self),
+            weight,
+            id: None,
+        }
+    }
+
+    /// Acquires a token of the given weight if there is enough capacity, and
+    /// no acquirer is waiting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is zero.
+    pub fn try_acquire(&self, weight: usize) -> Option<BoundedGroupToken> {
+        assert!(weight != 0, "weight must be nonzero");
+        let acquired = self.0.report().with(|queue| self.try_count(queue, weight));
+        acquired.then(|| unsafe { self.token(weight) })
+    }
+
+    /// Counts the weight of a new token if it fits and no acquirer is
+    /// waiting.
+    #[inline]
+    fn try_count(&self, queue: &Queue, weight: usize) -> bool {
+        let acquired = !queue.is_waiting() && queue.capacity - Queue::weight(&self.0) >= weight;
+        if acquired {
+            self.0.count_many(weight);
+        }
+        acquired
+    }
+
+    /// Returns a token whose weight has already been counted.
+    ///
+    /// # Safety
+    ///
+    /// `weight` clones must be counted for the token.
+    #[inline]
+    unsafe fn token(&self, weight: usize) -> BoundedGroupToken {
+        BoundedGroupToken {
+            token: unsafe { self.0.dup_counted() },
+            weight,
+        }
+    }
+}
+
+impl BoundedGroupToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns the weight of the token.
+    #[inline]
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+}
+
+impl Drop for BoundedGroupTokenFactory {
+    #[inline]
+    fn drop(&mut self) {
+        release(&self.0, 1);
+    }
+}
+
+impl Drop for BoundedGroupToken {
+    #[inline]
+    fn drop(&mut self) {
+        release(&self.token, self.weight);
+    }
+}
+
+impl Drop for Acquire<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let (Some(factory), Some(id)) = (self.factory, self.id) else {
+            return;
+        };
+        let twin = &factory.0;
+        let waker = twin.report().with(|queue| {
+            let index = queue.position(id);
+            let acquirer = queue.acquirers.remove(index).expect("queued acquirer");
+            if acquirer.state != AcquirerState::Queued {
+                // The factory is still alive, so this is never the last clone.
+                drop(unsafe { twin.release_many(acquirer.weight) });
+            }
+            queue.admit(twin)
+        });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for BoundedWaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_done(cx)
+    }
+}
+
+impl Waitable for BoundedWaitGroup {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.0.poll_shared(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.0.remove_waker(key);
+    }
+}
+
+impl Future for Acquire<'_> {
+    type Output = BoundedGroupToken;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let factory = this.factory.expect("`Acquire` polled after completion");
+        let weight = this.weight;
+        let (acquired, waker) = factory.0.report().with(|queue| {
+            let acquired = match this.id {
+                Some(id) => {
+                    let index = queue.position(id);
+                    let acquirer = &mut queue.acquirers[index];
+                    if acquirer.state == AcquirerState::Queued {
+                        acquirer.waker.clone_from(cx.waker());
+                        false
+                    } else {
+                        queue.acquirers.remove(index);
+                        true
+                    }
+                }
+                None if factory.try_count(queue, weight) => true,
+                None => {
+                    let id = queue.next_id;
+                    queue.next_id += 1;
+                    queue.acquirers.push_back(Acquirer {
+                        id,
+                        weight,
+                        waker: cx.waker().clone(),
+                        state: AcquirerState::Queued,
+                    });
+                    this.id = Some(id);
+                    false
+                }
+            };
+            // Wakes the next admitted acquirer, if any.
+            let waker = if acquired {
+                queue.admit(&factory.0)
+            } else {
+                None
+            };
+            (acquired, waker)
+        });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        if !acquired {
+            return Poll::Pending;
+        }
+        this.factory = None;
+        this.id = None;
+        Poll::Ready(unsafe { factory.token(weight) })
+    }
+}
+
+impl fmt::Debug for BoundedGroupTokenFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedGroupTokenFactory")
+            .field("capacity", &self.capacity())
+            .field("weight", &self.weight())
+            .finish()
+    }
+}
+
+impl fmt::Debug for BoundedGroupToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedGroupToken")
+            .field("weight", &self.weight)
+            .finish()
+    }
+}
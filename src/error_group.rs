@@ -0,0 +1,235 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use alloc::vec::Vec;
+
+use crate::{
+    layout::ReportLayout,
+    sync::{WaitGroupLayoutExt, WaitGroupWrapper},
+    twin_ref::{ClonableTwinRef, TwinRef},
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Strategy of collecting errors in an [`ErrorWaitGroup`].
+///
+/// Implemented by [`FirstError`] and [`AllErrors`].
+pub trait ErrorMode: sealed::Sealed + 'static {
+    /// Error type the [`ErrorWaitGroup`] resolves to.
+    type Error<E>;
+
+    #[doc(hidden)]
+    type Storage<E>: Default;
+
+    #[doc(hidden)]
+    fn push<E>(storage: &mut Self::Storage<E>, err: E);
+
+    #[doc(hidden)]
+    fn take<E>(storage: &mut Self::Storage<E>) -> Option<Self::Error<E>>;
+}
+
+/// Keeps the first reported error only.
+#[derive(Debug)]
+pub enum FirstError {}
+
+/// Keeps every reported error, in the order of reporting.
+#[derive(Debug)]
+pub enum AllErrors {}
+
+impl sealed::Sealed for FirstError {}
+impl sealed::Sealed for AllErrors {}
+
+impl ErrorMode for FirstError {
+    type Error<E> = E;
+    type Storage<E> = Option<E>;
+
+    #[inline]
+    fn push<E>(storage: &mut Self::Storage<E>, err: E) {
+        if storage.is_none() {
+            *storage = Some(err);
+        }
+    }
+
+    #[inline]
+    fn take<E>(storage: &mut Self::Storage<E>) -> Option<Self::Error<E>> {
+        storage.take()
+    }
+}
+
+impl ErrorMode for AllErrors {
+    type Error<E> = Vec<E>;
+    type Storage<E> = Vec<E>;
+
+    #[inline]
+    fn push<E>(storage: &mut Self::Storage<E>, err: E) {
+        storage.push(err);
+    }
+
+    #[inline]
+    fn take<E>(storage: &mut Self::Storage<E>) -> Option<Self::Error<E>> {
+        (!storage.is_empty()).then(|| core::mem::take(storage))
+    }
+}
+
+type ErrorLayout<E, M> = ReportLayout<<M as ErrorMode>::Storage<E>>;
+
+/// WaitGroup whose clonable group tokens can report errors.
+///
+/// Resolves to `Err` with the collected errors if any token has
+/// [failed](ErrorGroupToken::fail), or `Ok(())` otherwise. By default only the
+/// first error is kept; use [`AllErrors`] to keep every error.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// The errors are handed out on the first completion. It is safe to poll again
+/// after completion, which resolves to `Ok(())`.
+///
+/// ```rust
+/// # use compact_waitgroup::{AllErrors, ErrorWaitGroup};
+/// # futures_executor::block_on(async {
+/// let (wg, factory) = ErrorWaitGroup::<&str>::new();
+/// let token = factory.into_token();
+/// token.clone().fail("first");
+/// token.fail("second");
+/// assert_eq!(wg.await, Err("first"));
+///
+/// let (wg, factory) = ErrorWaitGroup::<&str, AllErrors>::new();
+/// let token = factory.into_token();
+/// token.clone().fail("first");
+/// token.fail("second");
+/// assert_eq!(wg.await, Err(vec!["first", "second"]));
+/// # });
+/// ```
+#[must_use]
+pub struct ErrorWaitGroup<E, M: ErrorMode = FirstError>(
+    WaitGroupWrapper<TwinRef<ErrorLayout<E, M>>>,
+);
+
+/// Clonable group token of [`ErrorWaitGroup`].
+///
+/// Dropping or releasing all tokens completes the associated
+/// [`ErrorWaitGroup`].
+#[must_use]
+pub struct ErrorGroupToken<E, M: ErrorMode = FirstError>(ClonableTwinRef<ErrorLayout<E, M>>);
+
+/// Factory of [`ErrorGroupToken`].
+///
+/// Provides methods to obtain or scope the clonable token for distribution.
+#[must_use]
+pub struct ErrorGroupTokenFactory<E, M: ErrorMode = FirstError>(ErrorGroupToken<E, M>);
+
+impl<E, M: ErrorMode> ErrorWaitGroup<E, M> {
+    /// Creates a new `ErrorWaitGroup` and an [`ErrorGroupTokenFactory`].
+    pub fn new() -> (Self, ErrorGroupTokenFactory<E, M>) {
+        let inner = ReportLayout::new(Default::default());
+        let (wg, token) = TwinRef::new_clonable(inner);
+        (
+            Self(WaitGroupWrapper::new(wg)),
+            ErrorGroupTokenFactory(ErrorGroupToken(token)),
+        )
+    }
+
+    /// Checks if the `ErrorWaitGroup` has completed.
+    ///
+    /// This returns `true` if all [`ErrorGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+}
+
+impl<E, M: ErrorMode> Future for ErrorWaitGroup<E, M> {
+    type Output = Result<(), M::Error<E>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        ready!(this.0.poll_done(cx));
+        Poll::Ready(match this.0.report().with(M::take) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        })
+    }
+}
+
+impl<E, M: ErrorMode> ErrorGroupTokenFactory<E, M> {
+    /// Consumes the inner token.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Extracts the inner [`ErrorGroupToken`].
+    #[inline]
+    pub fn into_token(self) -> ErrorGroupToken<E, M> {
+        self.0
+    }
+
+    /// Executes a closure with the inner [`ErrorGroupToken`].
+    #[inline]
+    pub fn scope<T, F: FnOnce(ErrorGroupToken<E, M>) -> T>(self, func: F) -> T {
+        func(self.into_token())
+    }
+}
+
+impl<E, M: ErrorMode> ErrorGroupToken<E, M> {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Reports an error and consumes the token.
+    #[inline]
+    pub fn fail(self, err: E) {
+        self.report(err);
+    }
+
+    /// Reports an error without consuming the token.
+    #[inline]
+    pub fn report(&self, err: E) {
+        self.0.report().with(|storage| M::push(storage, err));
+    }
+}
+
+impl<E, M: ErrorMode> Clone for ErrorGroupToken<E, M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E, M: ErrorMode> fmt::Debug for ErrorWaitGroup<E, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ErrorWaitGroup")
+            .field(&format_args!("done: {}", self.is_done()))
+            .finish()
+    }
+}
+
+impl<E, M: ErrorMode> fmt::Debug for ErrorGroupToken<E, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ErrorGroupToken")
+            .field(&format_args!("done: {}", self.0.is_done()))
+            .finish()
+    }
+}
+
+impl<E, M: ErrorMode> fmt::Debug for ErrorGroupTokenFactory<E, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ErrorGroupTokenFactory")
+            .field(&self.0)
+            .finish()
+    }
+}
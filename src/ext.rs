@@ -1,16 +1,29 @@
 use core::{
     pin::Pin,
-    task::{Context, Poll},
+    task::{Context, Poll, ready},
 };
 
+use alloc::vec::Vec;
+
 use derive_more::Into;
 use pin_project_lite::pin_project;
 
-use crate::{GroupToken, MonoGroupToken, group::GroupTokenFactory};
+use self::private::GroupTokenRelease;
+use crate::sync::WaitGroupLayoutExt;
+use crate::{
+    BoundedGroupToken, BoundedGroupTokenFactory, ErrorGroupToken, ErrorMode, GroupToken,
+    LabeledGroupToken, LabeledGroupTokenFactory, MonoGroupToken, MonoValueGroupToken,
+    SharedGroupToken, ShutdownToken, StartupGroupTokenFactory, TaskTrackerToken,
+    group::{GroupTokenFactory, SharedGroupTokenFactory},
+};
 
 /// Extension trait for futures to automatically release group tokens.
 pub trait GroupTokenExt<T>: Sized {
     /// Releases the group token when the future is ready or dropped.
+    ///
+    /// With an [`ErrorGroupToken`], the future must output a `Result`. An
+    /// `Err` is reported to the group, and the wrapper resolves to the `Ok`
+    /// value as an `Option`.
     #[inline]
     fn release_on_ready(self, token: T) -> GroupTokenReleaseOnReady<Self, T> {
         GroupTokenReleaseOnReady {
@@ -27,25 +40,262 @@ pub trait GroupTokenExt<T>: Sized {
     fn release_on_drop(self, token: T) -> GroupTokenReleaseOnDrop<Self, T> {
         GroupTokenReleaseOnDrop { inner: self, token }
     }
+
+    /// Releases the group token when the future is first polled or dropped.
+    ///
+    /// Useful to wait for spawned futures to start running.
+    #[inline]
+    fn release_on_start(self, token: T) -> GroupTokenReleaseOnStart<Self, T> {
+        GroupTokenReleaseOnStart {
+            inner: self,
+            token: Some(token),
+        }
+    }
+
+    /// Releases the group token when the future is ready or dropped, and
+    /// aborts the future once the waiter of the group is dropped.
+    ///
+    /// Resolves to [`Abandoned`](crate::Abandoned) if the future is aborted.
+    /// The aborted future is not polled anymore, and the token is released
+    /// immediately.
+    #[inline]
+    fn abort_on_abandon(self, token: T) -> GroupTokenAbortOnAbandon<Self, T>
+    where
+        T: private::GroupTokenAbandon,
+    {
+        GroupTokenAbortOnAbandon {
+            inner: self,
+            token: AbandonToken {
+                token: Some(token),
+                key: None,
+            },
+        }
+    }
 }
 
 /// Extension trait for `FnOnce` to automatically release group tokens.
 pub trait GroupTokenFuncExt<T, Output>: Sized {
     /// Releases the group token when the closure returns.
-    fn release_on_return(self, token: T) -> impl FnOnce() -> Output + Send;
+    ///
+    /// The returned closure is `Send` if the closure is `Send`.
+    fn release_on_return(self, token: T) -> impl FnOnce() -> Output;
+}
+
+/// Extension trait for `AsyncFnOnce` to automatically release group tokens.
+pub trait GroupTokenAsyncFuncExt<T, Output>: Sized {
+    /// Calls the closure, and releases the group token when its future is
+    /// ready or dropped.
+    ///
+    /// The token is handled like in [`GroupTokenExt::release_on_ready`]. The
+    /// returned future is `Send` if the future of the closure is `Send`.
+    fn release_on_complete(self, token: T) -> impl Future<Output = Output>;
+}
+
+/// Group token that is released by dropping it.
+///
+/// Every token and token factory of this crate implements this trait, except
+/// those of [`ErrorWaitGroup`](crate::ErrorWaitGroup) and `PanicWaitGroup`,
+/// which report the outcome of the work on release. It is also implemented
+/// for tuples, arrays, `Vec`s and `Option`s of tokens, to release tokens of
+/// several groups together, which enables [`GroupTokenExt`] and
+/// [`GroupTokenFuncExt`] for them.
+///
+/// This trait is sealed, and cannot be implemented outside of this crate.
+///
+/// ```rust
+/// # use compact_waitgroup::{GroupTokenExt, MonoWaitGroup, WaitGroup};
+/// # futures_executor::block_on(async {
+/// let (conn_wg, conn_token) = MonoWaitGroup::new();
+/// let (drain_wg, drain_factory) = WaitGroup::new();
+///
+/// async {}
+///     .release_on_ready((conn_token, drain_factory.into_token()))
+///     .await;
+///
+/// conn_wg.await;
+/// drain_wg.await;
+/// # });
+/// ```
+pub trait GroupTokenType: private::Sealed + Sync + Send + 'static {}
+
+macro_rules! impl_group_token_type {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl GroupTokenType for $ty {}
+        )+
+    };
+}
+
+impl_group_token_type!(
+    BoundedGroupToken,
+    BoundedGroupTokenFactory,
+    GroupToken,
+    GroupTokenFactory,
+    MonoGroupToken,
+    SharedGroupToken,
+    SharedGroupTokenFactory,
+    ShutdownToken,
+    StartupGroupTokenFactory,
+    TaskTrackerToken,
+);
+#[cfg(feature = "std")]
+impl_group_token_type!(
+    crate::HeartbeatGroupToken,
+    crate::HeartbeatGroupTokenFactory
+);
+
+impl<T: Send + 'static> private::Sealed for MonoValueGroupToken<T> {}
+impl<T: Send + 'static> GroupTokenType for MonoValueGroupToken<T> {}
+impl<K: Send + 'static> private::Sealed for LabeledGroupToken<K> {}
+impl<K: Send + 'static> GroupTokenType for LabeledGroupToken<K> {}
+impl<K: Send + 'static> private::Sealed for LabeledGroupTokenFactory<K> {}
+impl<K: Send + 'static> GroupTokenType for LabeledGroupTokenFactory<K> {}
+
+impl<T: GroupTokenType> private::Sealed for Option<T> {}
+impl<T: GroupTokenType> GroupTokenType for Option<T> {}
+impl<T: GroupTokenType> private::Sealed for Vec<T> {}
+impl<T: GroupTokenType> GroupTokenType for Vec<T> {}
+impl<T: GroupTokenType, const N: usize> private::Sealed for [T; N] {}
+impl<T: GroupTokenType, const N: usize> GroupTokenType for [T; N] {}
+
+macro_rules! impl_group_token_type_for_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty: GroupTokenType),+> private::Sealed for ($($ty,)+) {}
+        impl<$($ty: GroupTokenType),+> GroupTokenType for ($($ty,)+) {}
+    };
+}
+
+impl_group_token_type_for_tuple!(A);
+impl_group_token_type_for_tuple!(A, B);
+impl_group_token_type_for_tuple!(A, B, C);
+impl_group_token_type_for_tuple!(A, B, C, D);
+impl_group_token_type_for_tuple!(A, B, C, D, E);
+impl_group_token_type_for_tuple!(A, B, C, D, E, F);
+impl_group_token_type_for_tuple!(A, B, C, D, E, F, G);
+impl_group_token_type_for_tuple!(A, B, C, D, E, F, G, H);
+
+mod private {
+    /// Restricts [`GroupTokenType`](super::GroupTokenType) to the tokens of
+    /// this crate.
+    pub trait Sealed {}
+
+    /// Releases the token with the output of the future.
+    pub trait GroupTokenRelease<O>: Sized {
+        type Output;
+
+        fn release_with(token: Option<Self>, output: O) -> Self::Output;
+    }
+
+    /// Observes the waiter of the group being dropped.
+    pub trait GroupTokenAbandon {
+        fn poll_abandoned(
+            &self,
+            cx: &mut core::task::Context<'_>,
+            key: &mut Option<usize>,
+        ) -> core::task::Poll<()>;
+
+        fn remove_abandon_waker(&self, key: usize);
+    }
+}
+
+impl private::GroupTokenAbandon for GroupToken {
+    #[inline]
+    fn poll_abandoned(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.abandon_layout().poll_abandoned(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_abandon_waker(&self, key: usize) {
+        self.abandon_layout().remove_abandon_waker(key);
+    }
 }
 
-trait GroupTokenType: Sync + Send + 'static {}
+impl private::GroupTokenAbandon for MonoGroupToken {
+    #[inline]
+    fn poll_abandoned(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.abandon_layout().poll_abandoned(cx, Some(key))
+    }
 
-impl GroupTokenType for GroupTokenFactory {}
-impl GroupTokenType for GroupToken {}
-impl GroupTokenType for MonoGroupToken {}
+    #[inline]
+    fn remove_abandon_waker(&self, key: usize) {
+        self.abandon_layout().remove_abandon_waker(key);
+    }
+}
+
+impl<T: GroupTokenType, O> GroupTokenRelease<O> for T {
+    type Output = O;
+
+    #[inline]
+    fn release_with(token: Option<Self>, output: O) -> Self::Output {
+        drop(token);
+        output
+    }
+}
+
+impl<T, E, M: ErrorMode> GroupTokenRelease<Result<T, E>> for ErrorGroupToken<E, M> {
+    type Output = Option<T>;
+
+    #[inline]
+    fn release_with(token: Option<Self>, output: Result<T, E>) -> Self::Output {
+        match output {
+            Ok(val) => Some(val),
+            Err(err) => {
+                if let Some(token) = token {
+                    token.fail(err);
+                }
+                None
+            }
+        }
+    }
+}
 
 impl<T: GroupTokenType, F: Future> GroupTokenExt<T> for F {}
 
-impl<T: GroupTokenType, Output, F: Send + FnOnce() -> Output> GroupTokenFuncExt<T, Output> for F {
+#[cfg(feature = "std")]
+impl<O> GroupTokenRelease<O> for crate::PanicGroupToken {
+    type Output = O;
+
+    #[inline]
+    fn release_with(token: Option<Self>, output: O) -> Self::Output {
+        drop(token);
+        output
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Future> GroupTokenExt<crate::PanicGroupToken> for F {}
+
+/// Catches a panic of the closure, and poisons the group with its payload.
+///
+/// The returned closure outputs `None` if the closure has panicked.
+#[cfg(feature = "std")]
+impl<Output, F: FnOnce() -> Output> GroupTokenFuncExt<crate::PanicGroupToken, Option<Output>>
+    for F
+{
     #[inline]
-    fn release_on_return(self, token: T) -> impl FnOnce() -> Output + Send {
+    fn release_on_return(self, token: crate::PanicGroupToken) -> impl FnOnce() -> Option<Output> {
+        move || match std::panic::catch_unwind(core::panic::AssertUnwindSafe(self)) {
+            Ok(res) => {
+                drop(token);
+                Some(res)
+            }
+            Err(payload) => {
+                token.poison(payload);
+                None
+            }
+        }
+    }
+}
+
+impl<T, E, M: ErrorMode, F: Future<Output = Result<T, E>>> GroupTokenExt<ErrorGroupToken<E, M>>
+    for F
+{
+}
+
+impl<T: GroupTokenType, Output, F: FnOnce() -> Output> GroupTokenFuncExt<T, Output> for F {
+    #[inline]
+    fn release_on_return(self, token: T) -> impl FnOnce() -> Output {
         move || {
             let res = (self)();
             drop(token);
@@ -54,6 +304,16 @@ impl<T: GroupTokenType, Output, F: Send + FnOnce() -> Output> GroupTokenFuncExt<
     }
 }
 
+impl<T: GroupTokenRelease<O>, O, F: AsyncFnOnce() -> O> GroupTokenAsyncFuncExt<T, T::Output> for F {
+    #[inline]
+    fn release_on_complete(self, token: T) -> impl Future<Output = T::Output> {
+        GroupTokenReleaseOnReady {
+            inner: (self)(),
+            token: Some(token),
+        }
+    }
+}
+
 pin_project! {
     /// Wrapper that releases a token when the future is ready or dropped.
     ///
@@ -78,6 +338,40 @@ pin_project! {
     }
 }
 
+pin_project! {
+    /// Wrapper that releases a token when the future is first polled or
+    /// dropped.
+    ///
+    /// Created by [`GroupTokenExt::release_on_start`].
+    #[derive(Debug, Into)]
+    pub struct GroupTokenReleaseOnStart<F, T> {
+        #[pin]
+        inner: F,
+        token: Option<T>,
+    }
+}
+
+pin_project! {
+    /// Wrapper that releases a token when the future is ready or dropped, or
+    /// when the group is abandoned.
+    ///
+    /// Created by [`GroupTokenExt::abort_on_abandon`].
+    #[derive(Debug)]
+    pub struct GroupTokenAbortOnAbandon<F, T: private::GroupTokenAbandon> {
+        #[pin]
+        inner: F,
+        token: AbandonToken<T>,
+    }
+}
+
+/// Token of [`GroupTokenAbortOnAbandon`], whose waker is unregistered once
+/// the token is released.
+#[derive(Debug)]
+struct AbandonToken<T: private::GroupTokenAbandon> {
+    token: Option<T>,
+    key: Option<usize>,
+}
+
 impl<F, T> GroupTokenReleaseOnDrop<F, T> {
     /// Returns a pinned mutable reference to the inner future.
     #[inline]
@@ -106,6 +400,31 @@ impl<F, T> GroupTokenReleaseOnReady<F, T> {
     }
 }
 
+impl<F, T> GroupTokenReleaseOnStart<F, T> {
+    /// Returns a pinned mutable reference to the inner future.
+    #[inline]
+    pub fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut F> {
+        self.project().inner
+    }
+
+    /// Returns a reference to the associated token if not yet released.
+    #[inline]
+    pub fn group_token(&self) -> Option<&T> {
+        self.token.as_ref()
+    }
+}
+
+impl<F: Future, T> Future for GroupTokenReleaseOnStart<F, T> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        drop(this.token.take());
+        this.inner.poll(cx)
+    }
+}
+
 impl<F: Future, T> Future for GroupTokenReleaseOnDrop<F, T> {
     type Output = F::Output;
 
@@ -115,16 +434,75 @@ impl<F: Future, T> Future for GroupTokenReleaseOnDrop<F, T> {
     }
 }
 
-impl<F: Future, T> Future for GroupTokenReleaseOnReady<F, T> {
-    type Output = F::Output;
+impl<F: Future, T: GroupTokenRelease<F::Output>> Future for GroupTokenReleaseOnReady<F, T> {
+    type Output = T::Output;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = ready!(this.inner.poll(cx));
+        Poll::Ready(T::release_with(this.token.take(), res))
+    }
+}
+
+impl<F, T: private::GroupTokenAbandon> GroupTokenAbortOnAbandon<F, T> {
+    /// Returns a pinned mutable reference to the inner future.
+    #[inline]
+    pub fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut F> {
+        self.project().inner
+    }
+
+    /// Returns a reference to the associated token if not yet released.
+    #[inline]
+    pub fn group_token(&self) -> Option<&T> {
+        self.token.token.as_ref()
+    }
+}
 
+impl<F, T: private::GroupTokenAbandon> From<GroupTokenAbortOnAbandon<F, T>> for (F, Option<T>) {
     #[inline]
+    fn from(mut value: GroupTokenAbortOnAbandon<F, T>) -> Self {
+        let token = value.token.take();
+        (value.inner, token)
+    }
+}
+
+impl<F: Future, T: private::GroupTokenAbandon> Future for GroupTokenAbortOnAbandon<F, T> {
+    type Output = Result<F::Output, crate::Abandoned>;
+
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
-        let res = this.inner.poll(cx);
-        if res.is_ready() {
+        let AbandonToken {
+            token: Some(token),
+            key,
+        } = this.token
+        else {
+            panic!("`GroupTokenAbortOnAbandon` polled after completion");
+        };
+        if let Poll::Ready(res) = this.inner.poll(cx) {
             drop(this.token.take());
+            return Poll::Ready(Ok(res));
         }
-        res
+        ready!(token.poll_abandoned(cx, key));
+        drop(this.token.take());
+        Poll::Ready(Err(crate::Abandoned))
+    }
+}
+
+impl<T: private::GroupTokenAbandon> AbandonToken<T> {
+    /// Unregisters the waker and takes the token.
+    #[inline]
+    fn take(&mut self) -> Option<T> {
+        if let (Some(token), Some(key)) = (&self.token, self.key.take()) {
+            token.remove_abandon_waker(key);
+        }
+        self.token.take()
+    }
+}
+
+impl<T: private::GroupTokenAbandon> Drop for AbandonToken<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(self.take());
     }
 }
@@ -0,0 +1,166 @@
+use core::{
+    fmt,
+    panic::{RefUnwindSafe, UnwindSafe},
+    task::{Context, Poll},
+};
+
+use crate::{
+    group::{GroupClosed, GroupToken, Wait, WaitGroup, private::Waitable},
+    sync::{with_cell, with_cell_mut},
+    utils::*,
+};
+
+/// Gate that tracks entered tasks and can be closed to reject new ones.
+///
+/// Unlike [`WaitGroup`], the waiter can tell "every task has finished" apart
+/// from "no task has started yet": waiting only resolves once the gate is
+/// [closed](Self::close) and every [`GroupToken`] returned by
+/// [`enter`](Self::enter) has been released.
+///
+/// No allocation is needed beyond the one of the inner [`WaitGroup`].
+///
+/// # Cancellation safety
+///
+/// The future returned by [`wait`](Self::wait) is cancellation safe.
+///
+/// ```rust
+/// # use compact_waitgroup::Gate;
+/// # futures_executor::block_on(async {
+/// let gate = Gate::new();
+///
+/// let token = gate.enter().unwrap();
+/// assert!(!gate.is_done());
+///
+/// gate.close();
+/// assert!(gate.enter().is_err());
+/// assert!(!gate.is_done());
+///
+/// token.release();
+/// gate.wait().await;
+/// assert!(gate.is_done());
+/// # });
+/// ```
+#[must_use]
+pub struct Gate {
+    wg: WaitGroup,
+    entering: AtomicUsize,
+    token: UnsafeCell<Option<GroupToken>>,
+}
+
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
+unsafe impl Send for Gate {}
+unsafe impl Sync for Gate {}
+impl UnwindSafe for Gate {}
+impl RefUnwindSafe for Gate {}
+
+impl Gate {
+    /// Creates a new open `Gate`.
+    pub fn new() -> Self {
+        let (wg, factory) = WaitGroup::new();
+        Self {
+            wg,
+            entering: AtomicUsize::new(0),
+            token: UnsafeCell::new(Some(factory.into_token())),
+        }
+    }
+
+    /// Enters the gate, returning a [`GroupToken`] that keeps the gate from
+    /// completing until released.
+    ///
+    /// Returns [`GroupClosed`] if the gate has been closed.
+    pub fn enter(&self) -> Result<GroupToken, GroupClosed> {
+        let prev = self.entering.fetch_add(1, atomic::Acquire);
+        let res = if prev & CLOSED == 0 {
+            // Concurrent `enter` calls only read the token, which `close`
+            // takes once they are all done.
+            let token = unsafe { with_cell(&self.token, Option::clone) };
+            token.ok_or(GroupClosed)
+        } else {
+            Err(GroupClosed)
+        };
+        self.entering.fetch_sub(1, atomic::Release);
+        res
+    }
+
+    /// Closes the gate, so that [`enter`](Self::enter) returns
+    /// [`GroupClosed`] from now on.
+    ///
+    /// Closing an already closed gate does nothing.
+    pub fn close(&self) {
+        if self.entering.fetch_or(CLOSED, atomic::AcqRel) & CLOSED != 0 {
+            return;
+        }
+        // Wait for concurrent `enter` calls that may still be cloning the
+        // token.
+        while self.entering.load(atomic::Acquire) != CLOSED {
+            spin_loop();
+        }
+        drop(unsafe { with_cell_mut(&self.token, Option::take) });
+    }
+
+    /// Checks if the `Gate` has been closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.entering.load(atomic::Relaxed) & CLOSED != 0
+    }
+
+    /// Checks if the `Gate` has completed.
+    ///
+    /// This returns `true` if the gate is closed and all entered
+    /// [`GroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.wg.is_done()
+    }
+
+    /// Returns the number of entered [`GroupToken`]s still alive.
+    pub fn outstanding(&self) -> usize {
+        let closed = self.is_closed();
+        // The token held by an open gate is not counted.
+        self.wg.outstanding().saturating_sub(usize::from(!closed))
+    }
+
+    /// Polls the `Gate` for completion through a shared reference.
+    ///
+    /// Registers the waker as [`WaitGroup::poll_done`] does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.wg.poll_done(cx)
+    }
+
+    /// Returns a future that waits for the `Gate` to be closed and drained.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+}
+
+impl Waitable for Gate {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.wg.poll_wait(cx, key)
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.wg.remove_waker(key);
+    }
+}
+
+impl Default for Gate {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gate")
+            .field("closed", &self.is_closed())
+            .field("done", &self.is_done())
+            .field("outstanding", &self.outstanding())
+            .finish()
+    }
+}
@@ -1,14 +1,18 @@
 use core::{
+    fmt,
     pin::Pin,
-    task::{Context, Poll},
+    task::{Context, Poll, Waker, ready},
 };
 
-use derive_more::{Debug, Into};
+use derive_more::{Debug, Deref, Into};
+use pin_project_lite::pin_project;
 
 use crate::{
-    layout::SharedLayout,
-    sync::{WaitGroupLayoutExt, WaitGroupWrapper},
+    ext::{GroupTokenExt, GroupTokenReleaseOnReady, GroupTokenReleaseOnStart},
+    layout::{MultiLayout, NestedLayout, SharedLayout, ValueLayout, WaiterSide},
+    sync::{MultiWaitGroupLayoutExt, WaitGroupLayoutExt, WaitGroupWrapper},
     twin_ref::{ClonableTwinRef, TwinRef},
+    utils::*,
 };
 
 #[cfg(feature = "compact-mono")]
@@ -16,6 +20,10 @@ type MonoLayout = crate::layout::MonoLayout;
 #[cfg(not(feature = "compact-mono"))]
 type MonoLayout = crate::layout::SharedLayout;
 
+/// Future tracked by [`StartupGroupTokenFactory::track`].
+pub type StartupTracked<F> =
+    GroupTokenReleaseOnReady<GroupTokenReleaseOnStart<F, GroupToken>, GroupToken>;
+
 /// WaitGroup with clonable group tokens.
 ///
 /// # Cancellation safety
@@ -44,7 +52,10 @@ type MonoLayout = crate::layout::SharedLayout;
 /// ```
 #[must_use]
 #[derive(Debug)]
-pub struct WaitGroup(#[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef<SharedLayout>>);
+pub struct WaitGroup(
+    #[debug("done: {}, outstanding: {}", _0.is_done(), _0.outstanding())]
+    WaitGroupWrapper<TwinRef<SharedLayout>>,
+);
 
 /// WaitGroup with a single non-clonable group token.
 ///
@@ -79,17 +90,169 @@ pub struct WaitGroup(#[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef
 #[derive(Debug)]
 pub struct MonoWaitGroup(#[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef<MonoLayout>>);
 
+/// MonoWaitGroup that resolves to a value sent by its single token.
+///
+/// Works like a oneshot channel: the [`MonoValueGroupToken`] sends a value
+/// with [`complete`](MonoValueGroupToken::complete), and the waiter resolves
+/// to `Some(value)`. It resolves to `None` if the token is dropped without a
+/// value.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// The value is handed out on the first completion. It is safe to poll again
+/// after completion, which resolves to `None`.
+///
+/// ```rust
+/// # use compact_waitgroup::MonoValueWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (wg, token) = MonoValueWaitGroup::new();
+/// std::thread::spawn(move || token.complete(42));
+/// assert_eq!(wg.await, Some(42));
+///
+/// let (wg, token) = MonoValueWaitGroup::<u32>::new();
+/// token.release();
+/// assert_eq!(wg.await, None);
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct MonoValueWaitGroup<T>(
+    #[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef<ValueLayout<T>>>,
+);
+
+/// WaitGroup with clonable group tokens and clonable waiters.
+///
+/// Unlike [`WaitGroup`], this variant can be cloned and awaited by multiple
+/// tasks at the same time. Every registered waiter is woken when the group
+/// completes.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// It is also safe to poll again after completion.
+///
+/// ```rust
+/// # use compact_waitgroup::SharedWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (wg, token) = SharedWaitGroup::new();
+/// let wg_cloned = wg.clone();
+///
+/// assert!(!wg.is_done());
+///
+/// token.release();
+///
+/// wg.await;
+/// assert!(wg_cloned.is_done());
+///
+/// // Other waiters complete as well
+/// wg_cloned.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct SharedWaitGroup {
+    #[debug("done: {}", waiter.is_done())]
+    waiter: ClonableTwinRef<MultiLayout, WaiterSide>,
+    #[debug(skip)]
+    key: Option<usize>,
+}
+
+/// Reusable [`WaitGroup`] with generations.
+///
+/// Once completed, the group can be [`reset`](Self::reset) to start a new
+/// round backed by the same allocation. Each reset bumps the generation.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// It is also safe to poll again after completion.
+///
+/// ```rust
+/// # use compact_waitgroup::ReusableWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (mut wg, factory) = ReusableWaitGroup::new();
+/// assert_eq!(wg.generation(), 0);
+///
+/// // A group can only be reset once completed
+/// let token = factory.into_token();
+/// assert!(wg.reset().is_none());
+///
+/// token.release();
+/// (&mut wg).await;
+///
+/// let factory = wg.reset().unwrap();
+/// assert_eq!(wg.generation(), 1);
+/// assert!(!wg.is_done());
+///
+/// factory.release();
+/// wg.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug, Deref)]
+pub struct ReusableWaitGroup {
+    #[deref]
+    wg: WaitGroup,
+    generation: usize,
+}
+
+/// WaitGroup with a Go-style counter instead of group tokens.
+///
+/// The counter is increased by [`add`](Self::add) and decreased by
+/// [`done`](Self::done). The group completes when the counter drops to zero,
+/// or when it is waited on while the counter is zero. Once completed, the group
+/// is closed and [`add`](Self::add) returns an error.
+///
+/// No allocation is needed, so it can be placed in a `static`, an `Arc` or
+/// any other struct.
+///
+/// # Cancellation safety
+///
+/// The future returned by [`wait`](Self::wait) is cancellation safe.
+///
+/// ```rust
+/// # use compact_waitgroup::CounterWaitGroup;
+/// # futures_executor::block_on(async {
+/// let wg = CounterWaitGroup::new();
+///
+/// wg.add(2).unwrap();
+/// assert_eq!(wg.count(), 2);
+///
+/// wg.done();
+/// wg.done();
+///
+/// wg.wait().await;
+/// assert!(wg.is_done());
+/// assert!(wg.add(1).is_err());
+/// # });
+/// ```
+#[must_use]
+pub struct CounterWaitGroup {
+    count: AtomicUsize,
+    inner: WaitGroupWrapper<crate::layout::MonoLayout>,
+}
+
+/// Error returned when entering a group that has been closed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupClosed;
+
+/// Error returned when the waiter of a group has been dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Abandoned;
+
 /// Clonable group token.
 ///
 /// Used by [`WaitGroup`] to signal task completion. Can be cloned and
 /// distributed among multiple worker tasks. Dropping or releasing all tokens
 /// completes the associated [`WaitGroup`].
 #[must_use]
-#[derive(Clone, Debug)]
 pub struct GroupToken(
-    #[allow(unused)]
-    #[debug("done: {}", _0.is_done())]
     ClonableTwinRef<SharedLayout>,
+    #[cfg(feature = "stats")] crate::stats::TokenLifetime,
 );
 
 /// Non-clonable group token.
@@ -100,6 +263,43 @@ pub struct GroupToken(
 #[derive(Debug)]
 pub struct MonoGroupToken(#[debug("done: {}", _0.is_done())] TwinRef<MonoLayout>);
 
+/// Clonable group token of [`SharedWaitGroup`].
+///
+/// Dropping or releasing all tokens completes the associated
+/// [`SharedWaitGroup`].
+#[must_use]
+#[derive(Clone, Debug)]
+pub struct SharedGroupToken(
+    #[allow(unused)]
+    #[debug("done: {}", _0.is_done())]
+    ClonableTwinRef<MultiLayout>,
+);
+
+/// Point-in-time state of a [`WaitGroup`].
+///
+/// Created by [`WaitGroup::snapshot`]. The state may change as soon as it is
+/// taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WaitGroupSnapshot {
+    /// Number of [`GroupToken`]s still alive, including the one held by the
+    /// [`GroupTokenFactory`].
+    pub outstanding: usize,
+    /// Whether a waker is registered to be notified on completion.
+    pub waker_registered: bool,
+    /// Whether the [`WaitGroup`] has completed.
+    pub done: bool,
+}
+
+/// Non-clonable group token sending a value.
+///
+/// Used by [`MonoValueWaitGroup`] for a single worker task. Completing,
+/// dropping or releasing this token completes the associated
+/// [`MonoValueWaitGroup`].
+#[must_use]
+#[derive(Debug)]
+pub struct MonoValueGroupToken<T>(#[debug("done: {}", _0.is_done())] TwinRef<ValueLayout<T>>);
+
 /// Factory of [`GroupToken`].
 ///
 /// Provides methods to obtain or scope the clonable token for distribution.
@@ -107,6 +307,157 @@ pub struct MonoGroupToken(#[debug("done: {}", _0.is_done())] TwinRef<MonoLayout>
 #[derive(Debug, Into)]
 pub struct GroupTokenFactory(GroupToken);
 
+/// Factory of [`SharedGroupToken`].
+///
+/// Provides methods to obtain or scope the clonable token for distribution.
+#[must_use]
+#[derive(Debug, Into)]
+pub struct SharedGroupTokenFactory(SharedGroupToken);
+
+/// Factory of pairs of [`GroupToken`]s, tracking both the start and the end
+/// of tasks.
+///
+/// Each task holds a token of a "started" group, released on its first
+/// poll, and a token of a "finished" group, released once it is ready.
+///
+/// ```rust
+/// # use compact_waitgroup::StartupGroupTokenFactory;
+/// # futures_executor::block_on(async {
+/// let (started, finished, factory) = StartupGroupTokenFactory::new();
+/// let task = factory.track(async {
+///     // Bind listeners
+/// });
+/// factory.release();
+///
+/// assert!(!started.is_done());
+/// task.await;
+/// started.await;
+/// finished.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct StartupGroupTokenFactory {
+    started: GroupToken,
+    finished: GroupToken,
+}
+
+/// Future that waits for a group through a shared reference.
+///
+/// Created by the `wait` methods, such as [`WaitGroup::wait`]. Its waker is
+/// unregistered once it is dropped.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct Wait<'a, W: private::Waitable> {
+    wg: &'a W,
+    #[debug(skip)]
+    key: Option<usize>,
+}
+
+impl<'a, W: private::Waitable> Wait<'a, W> {
+    #[inline]
+    pub(crate) fn new(wg: &'a W) -> Self {
+        Self { wg, key: None }
+    }
+}
+
+pub(crate) mod private {
+    /// Group waited for through a shared reference by [`Wait`](super::Wait).
+    pub trait Waitable {
+        /// Polls for completion, registering the waker of `cx` under `key`.
+        fn poll_wait(
+            &self,
+            cx: &mut core::task::Context<'_>,
+            key: &mut Option<usize>,
+        ) -> core::task::Poll<()>;
+
+        /// Removes the waker registered under `key`.
+        fn remove_waker(&self, key: usize);
+    }
+}
+
+/// Future that waits for a [`SharedWaitGroup`] through a shared reference.
+///
+/// Created by [`SharedWaitGroup::wait`]. Its waker is unregistered once it
+/// is dropped.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct WaitShared<'a> {
+    #[debug("done: {}", wg.is_done())]
+    wg: &'a SharedWaitGroup,
+    #[debug(skip)]
+    key: Option<usize>,
+}
+
+/// Future that waits for the waiter of a group to be dropped.
+///
+/// Created by the `abandoned` methods, such as [`GroupToken::abandoned`].
+/// Its waker is unregistered once it is dropped.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct WaitAbandoned<'a, T> {
+    #[debug("abandoned: {}", layout.is_abandoned())]
+    layout: &'a crate::layout::MonoLayout,
+    #[debug(skip)]
+    key: Option<usize>,
+    #[debug(skip)]
+    _token: core::marker::PhantomData<&'a T>,
+}
+
+pin_project! {
+    /// Future that waits for a group until a timer fires.
+    ///
+    /// Created by the `with_timeout` methods, such as
+    /// [`WaitGroup::with_timeout`].
+    ///
+    /// # Cancellation safety
+    ///
+    /// This future is cancellation safe.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    #[derive(Debug)]
+    pub struct WithTimeout<W, T> {
+        inner: Option<W>,
+        #[pin]
+        timer: T,
+    }
+}
+
+/// Error returned when the timer of a [`WithTimeout`] fires first.
+///
+/// Holds the still pending waiter, which can be waited on again.
+#[derive(Debug)]
+pub struct TimedOut<W>(W);
+
+impl<W, T> WithTimeout<W, T> {
+    #[inline]
+    pub(crate) fn new(inner: W, timer: T) -> Self {
+        Self {
+            inner: Some(inner),
+            timer,
+        }
+    }
+}
+
+impl<W> TimedOut<W> {
+    /// Returns the still pending waiter.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
 impl WaitGroup {
     /// Creates a new `WaitGroup` and a [`GroupTokenFactory`].
     pub fn new() -> (Self, GroupTokenFactory) {
@@ -114,7 +465,7 @@ impl WaitGroup {
         let (wg, token) = TwinRef::new_clonable(inner);
         (
             Self(WaitGroupWrapper::new(wg)),
-            GroupTokenFactory(GroupToken(token)),
+            GroupTokenFactory(GroupToken::new(token)),
         )
     }
 
@@ -125,6 +476,161 @@ impl WaitGroup {
     pub fn is_done(&self) -> bool {
         self.0.is_done()
     }
+
+    /// Returns the number of [`GroupToken`]s still alive.
+    ///
+    /// The token held by the [`GroupTokenFactory`] is counted as well.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.0.outstanding()
+    }
+
+    /// Returns the lifetime statistics of the [`GroupToken`]s.
+    ///
+    /// The statistics are complete once the `WaitGroup` has completed.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "stats")]
+    /// # futures_executor::block_on(async {
+    /// # use compact_waitgroup::WaitGroup;
+    /// let (wg, factory) = WaitGroup::new();
+    /// let token = factory.into_token();
+    /// let tokens = [token.clone(), token.clone()];
+    /// drop((token, tokens));
+    /// wg.wait().await;
+    ///
+    /// let stats = wg.stats();
+    /// assert_eq!(stats.created, 3);
+    /// assert_eq!(stats.released, 3);
+    /// assert_eq!(stats.peak_outstanding, 3);
+    /// assert_eq!(stats.lifetimes.iter().sum::<usize>(), 3);
+    /// # });
+    /// ```
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> crate::TokenStats {
+        crate::stats::snapshot(&self.0)
+    }
+
+    /// Takes a snapshot of the current state.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::WaitGroup;
+    /// let (wg, factory) = WaitGroup::new();
+    /// let token = factory.into_token();
+    /// let token_cloned = token.clone();
+    ///
+    /// let snapshot = wg.snapshot();
+    /// assert_eq!(snapshot.outstanding, 2);
+    /// assert!(!snapshot.waker_registered);
+    /// assert!(!snapshot.done);
+    ///
+    /// drop((token, token_cloned));
+    /// assert!(wg.snapshot().done);
+    /// ```
+    pub fn snapshot(&self) -> WaitGroupSnapshot {
+        let waker_registered = self.0.has_waker();
+        let done = self.0.is_done();
+        WaitGroupSnapshot {
+            outstanding: if done { 0 } else { self.outstanding() },
+            waker_registered,
+            done,
+        }
+    }
+
+    /// Polls the `WaitGroup` for completion through a shared reference.
+    ///
+    /// Concurrent polls are allowed, and every registered waker is notified
+    /// on completion.
+    ///
+    /// The wakers registered here are only deduplicated, and kept until the
+    /// group completes or the `WaitGroup` is dropped. [`wait`](Self::wait)
+    /// unregisters the waker once the future is dropped instead.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_shared(cx, None)
+    }
+
+    /// Returns a future that waits for the `WaitGroup` through a shared
+    /// reference.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::WaitGroup;
+    /// # futures_executor::block_on(async {
+    /// let (wg, token) = WaitGroup::new();
+    /// let wg = std::sync::Arc::new(wg);
+    ///
+    /// token.release();
+    ///
+    /// wg.wait().await;
+    /// assert!(wg.is_done());
+    /// # });
+    /// ```
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+
+    /// Waits for the `WaitGroup` until `timer` is ready.
+    ///
+    /// Any `Future<Output = ()>` can be used as the timer, such as
+    /// `tokio::time::sleep`. Resolves to [`TimedOut`] holding the `WaitGroup`
+    /// if the timer fires before the last [`GroupToken`] is released.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::{WaitGroup};
+    /// # futures_executor::block_on(async {
+    /// let (wg, token) = WaitGroup::new();
+    ///
+    /// let timed_out = wg.with_timeout(async {}).await.unwrap_err();
+    /// let wg = timed_out.into_inner();
+    ///
+    /// token.release();
+    /// assert!(wg.with_timeout(core::future::pending()).await.is_ok());
+    /// # });
+    /// ```
+    #[inline]
+    pub fn with_timeout<T: Future<Output = ()>>(self, timer: T) -> WithTimeout<Self, T> {
+        WithTimeout::new(self, timer)
+    }
+
+    /// Blocks the current thread until the `WaitGroup` has completed.
+    ///
+    /// The calling thread is parked and unparked once the last [`GroupToken`] has been
+    /// released.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn wait_blocking(&mut self) {
+        if !self.is_done() {
+            crate::blocking::park_on(self, None);
+        }
+    }
+
+    /// Blocks the current thread until the `WaitGroup` has completed, or the
+    /// `timeout` has elapsed.
+    ///
+    /// Returns `true` if the `WaitGroup` has completed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn wait_timeout(&mut self, timeout: std::time::Duration) -> bool {
+        match std::time::Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => {
+                self.wait_blocking();
+                true
+            }
+        }
+    }
+
+    /// Blocks the current thread until the `WaitGroup` has completed, or the
+    /// `deadline` has been reached.
+    ///
+    /// Returns `true` if the `WaitGroup` has completed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn wait_deadline(&mut self, deadline: std::time::Instant) -> bool {
+        self.is_done() || crate::blocking::park_on(self, Some(deadline))
+    }
 }
 
 impl MonoWaitGroup {
@@ -142,68 +648,806 @@ impl MonoWaitGroup {
     pub fn is_done(&self) -> bool {
         self.0.is_done()
     }
-}
-
-impl Future for WaitGroup {
-    type Output = ();
 
+    /// Polls the `MonoWaitGroup` for completion through a shared reference.
+    ///
+    /// Registers the waker as [`WaitGroup::poll_done`] does.
     #[inline]
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        Pin::new(&mut self.0).poll(cx)
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_shared(cx, None)
     }
-}
-
-impl Future for MonoWaitGroup {
-    type Output = ();
 
+    /// Returns a future that waits for the `MonoWaitGroup` through a shared
+    /// reference.
     #[inline]
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        Pin::new(&mut self.0).poll(cx)
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
     }
-}
 
-impl GroupTokenFactory {
-    /// Consumes the inner token.
+    /// Waits for the `MonoWaitGroup` until `timer` is ready.
     ///
-    /// This is equivalent to dropping the factory.
+    /// Any `Future<Output = ()>` can be used as the timer, such as
+    /// `tokio::time::sleep`. Resolves to [`TimedOut`] holding the `MonoWaitGroup`
+    /// if the timer fires before the [`MonoGroupToken`] is released.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::{MonoWaitGroup};
+    /// # futures_executor::block_on(async {
+    /// let (wg, token) = MonoWaitGroup::new();
+    ///
+    /// let timed_out = wg.with_timeout(async {}).await.unwrap_err();
+    /// let wg = timed_out.into_inner();
+    ///
+    /// token.release();
+    /// assert!(wg.with_timeout(core::future::pending()).await.is_ok());
+    /// # });
+    /// ```
     #[inline]
-    pub fn release(self) {
-        drop(self);
+    pub fn with_timeout<T: Future<Output = ()>>(self, timer: T) -> WithTimeout<Self, T> {
+        WithTimeout::new(self, timer)
     }
 
-    /// Extracts the inner [`GroupToken`].
+    /// Blocks the current thread until the `MonoWaitGroup` has completed.
+    ///
+    /// The calling thread is parked and unparked once the [`MonoGroupToken`] has been
+    /// released.
+    #[cfg(feature = "std")]
     #[inline]
-    pub fn into_token(self) -> GroupToken {
-        self.0
+    pub fn wait_blocking(&mut self) {
+        if !self.is_done() {
+            crate::blocking::park_on(self, None);
+        }
     }
 
-    /// Executes a closure with the inner [`GroupToken`].
+    /// Blocks the current thread until the `MonoWaitGroup` has completed, or the
+    /// `timeout` has elapsed.
+    ///
+    /// Returns `true` if the `MonoWaitGroup` has completed.
+    #[cfg(feature = "std")]
     #[inline]
-    pub fn scope<T, F: FnOnce(GroupToken) -> T>(self, func: F) -> T {
-        func(self.into_token())
+    pub fn wait_timeout(&mut self, timeout: std::time::Duration) -> bool {
+        match std::time::Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => {
+                self.wait_blocking();
+                true
+            }
+        }
     }
-}
 
-impl GroupToken {
-    /// Consumes the token.
+    /// Blocks the current thread until the `MonoWaitGroup` has completed, or the
+    /// `deadline` has been reached.
     ///
-    /// This is equivalent to dropping the token.
+    /// Returns `true` if the `MonoWaitGroup` has completed.
+    #[cfg(feature = "std")]
     #[inline]
-    pub fn release(self) {
-        drop(self);
+    pub fn wait_deadline(&mut self, deadline: std::time::Instant) -> bool {
+        self.is_done() || crate::blocking::park_on(self, Some(deadline))
     }
 }
 
-impl MonoGroupToken {
-    /// Consumes the token.
-    ///
-    /// This is equivalent to dropping the token.
-    #[inline]
-    pub fn release(self) {
-        drop(self);
+impl<T> MonoValueWaitGroup<T> {
+    /// Creates a new `MonoValueWaitGroup` and a single
+    /// [`MonoValueGroupToken`].
+    pub fn new() -> (Self, MonoValueGroupToken<T>) {
+        let inner = ValueLayout::new();
+        let (wg, token) = TwinRef::new_mono(inner);
+        (Self(WaitGroupWrapper::new(wg)), MonoValueGroupToken(token))
     }
 
-    /// Returns the token itself.
+    /// Checks if the `MonoValueWaitGroup` has completed.
+    ///
+    /// This returns `true` if the [`MonoValueGroupToken`] has been completed
+    /// or dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+}
+
+impl SharedWaitGroup {
+    /// Creates a new `SharedWaitGroup` and a [`SharedGroupTokenFactory`].
+    pub fn new() -> (Self, SharedGroupTokenFactory) {
+        let inner = MultiLayout::new();
+        let (waiter, token) = ClonableTwinRef::new_twin_clonable(inner);
+        (
+            Self { waiter, key: None },
+            SharedGroupTokenFactory(SharedGroupToken(token)),
+        )
+    }
+
+    /// Checks if the `SharedWaitGroup` has completed.
+    ///
+    /// This returns `true` if all [`SharedGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.waiter.is_done()
+    }
+
+    /// Polls the `SharedWaitGroup` for completion through a shared reference.
+    ///
+    /// Concurrent polls are allowed, and every registered waker is notified
+    /// on completion.
+    ///
+    /// The wakers registered here are only deduplicated, and kept until the
+    /// group completes or every waiter is dropped. Awaiting the
+    /// `SharedWaitGroup` or [`wait`](Self::wait) unregisters the waker once
+    /// the future is dropped instead.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        unsafe { self.waiter.poll_shared(cx, None) }
+    }
+
+    /// Returns a future that waits for the `SharedWaitGroup` through a shared
+    /// reference.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::SharedWaitGroup;
+    /// # futures_executor::block_on(async {
+    /// let (wg, token) = SharedWaitGroup::new();
+    /// let wg = std::sync::Arc::new(wg);
+    ///
+    /// token.release();
+    ///
+    /// wg.wait().await;
+    /// assert!(wg.is_done());
+    /// # });
+    /// ```
+    #[inline]
+    pub fn wait(&self) -> WaitShared<'_> {
+        WaitShared {
+            wg: self,
+            key: None,
+        }
+    }
+
+    /// Returns the number of [`SharedGroupToken`]s still alive.
+    #[inline]
+    pub(crate) fn outstanding(&self) -> usize {
+        self.waiter.outstanding()
+    }
+
+    /// Creates a new [`SharedGroupToken`], unless the group has completed.
+    #[inline]
+    pub(crate) fn try_token(&self) -> Option<SharedGroupToken> {
+        self.waiter.try_upgrade().map(SharedGroupToken)
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: Option<usize>) {
+        if let Some(key) = key {
+            unsafe { self.waiter.remove_waker(key) }
+        }
+    }
+}
+
+impl ReusableWaitGroup {
+    /// Creates a new `ReusableWaitGroup` and a [`GroupTokenFactory`].
+    pub fn new() -> (Self, GroupTokenFactory) {
+        let (wg, factory) = WaitGroup::new();
+        (Self { wg, generation: 0 }, factory)
+    }
+
+    /// Returns the current generation, which starts at `0` and is bumped by
+    /// every [`reset`](Self::reset).
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Starts a new round if the current one has completed.
+    ///
+    /// Returns a new [`GroupTokenFactory`] backed by the same allocation, or
+    /// `None` if there are still outstanding [`GroupToken`]s. Tokens of
+    /// previous generations are all dropped by then, so they can never affect
+    /// the new round.
+    ///
+    /// With the `stats` feature, the statistics restart from the new round.
+    pub fn reset(&mut self) -> Option<GroupTokenFactory> {
+        if !self.wg.is_done() {
+            return None;
+        }
+        let token = loop {
+            // The last token may still be releasing its reference just after
+            // sending done.
+            let twin_ref = self.wg.0.get_mut();
+            let token = twin_ref.try_rearm_clonable(|layout| {
+                unsafe { layout.reset() };
+                #[cfg(feature = "stats")]
+                layout.stats().reset();
+            });
+            if let Some(token) = token {
+                break token;
+            }
+            spin_loop();
+        };
+        self.generation = self.generation.wrapping_add(1);
+        Some(GroupTokenFactory(GroupToken::new(token)))
+    }
+}
+
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
+impl CounterWaitGroup {
+    /// Creates a new `CounterWaitGroup` with a zero counter.
+    pub fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            inner: WaitGroupWrapper::new(crate::layout::MonoLayout::new()),
+        }
+    }
+
+    /// Adds `delta` to the counter.
+    ///
+    /// Returns [`GroupClosed`] if the group has already completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows.
+    pub fn add(&self, delta: usize) -> Result<(), GroupClosed> {
+        self.count
+            .fetch_update(atomic::Relaxed, atomic::Relaxed, |count| {
+                if count & CLOSED != 0 {
+                    return None;
+                }
+                match count.checked_add(delta) {
+                    Some(count) if count & CLOSED == 0 => Some(count),
+                    _ => panic!("counter overflow"),
+                }
+            })
+            .map(drop)
+            .map_err(|_| GroupClosed)
+    }
+
+    /// Decrements the counter by one.
+    ///
+    /// Completes the group if the counter drops to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter is already zero.
+    pub fn done(&self) {
+        let prev = self
+            .count
+            .fetch_update(atomic::AcqRel, atomic::Relaxed, |count| match count {
+                1 => Some(CLOSED),
+                count if count & !CLOSED == 0 => None,
+                count => Some(count - 1),
+            });
+        match prev {
+            Ok(1) => unsafe { self.inner.send_done() },
+            Ok(_) => {}
+            Err(_) => panic!("negative counter"),
+        }
+    }
+
+    /// Returns the current value of the counter.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count.load(atomic::Relaxed) & !CLOSED
+    }
+
+    /// Checks if the `CounterWaitGroup` has completed.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.inner.is_done()
+    }
+
+    /// Polls the `CounterWaitGroup` for completion through a shared
+    /// reference.
+    ///
+    /// The group completes immediately if the counter is zero. Registers the
+    /// waker as [`WaitGroup::poll_done`] does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.poll_keyed(cx, None)
+    }
+
+    fn poll_keyed(&self, cx: &mut Context<'_>, key: Option<&mut Option<usize>>) -> Poll<()> {
+        if self
+            .count
+            .compare_exchange(0, CLOSED, atomic::AcqRel, atomic::Relaxed)
+            .is_ok()
+        {
+            unsafe { self.inner.send_done() };
+        }
+        self.inner.poll_shared(cx, key)
+    }
+
+    /// Returns a future that waits for the counter to drop to zero.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+}
+
+impl fmt::Debug for CounterWaitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CounterWaitGroup")
+            .field("count", &self.count())
+            .field("done", &self.is_done())
+            .finish()
+    }
+}
+
+impl fmt::Debug for GroupToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GroupToken")
+            .field(&format_args!(
+                "done: {}, outstanding: {}",
+                self.0.is_done(),
+                self.0.outstanding()
+            ))
+            .finish()
+    }
+}
+
+impl Default for CounterWaitGroup {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> fmt::Display for TimedOut<W> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("group timed out")
+    }
+}
+
+impl<W: fmt::Debug> core::error::Error for TimedOut<W> {}
+
+impl fmt::Display for GroupClosed {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("group is closed")
+    }
+}
+
+impl core::error::Error for GroupClosed {}
+
+impl fmt::Display for Abandoned {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("group is abandoned")
+    }
+}
+
+impl core::error::Error for Abandoned {}
+
+impl Future for WaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_done(cx)
+    }
+}
+
+impl Future for MonoWaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_done(cx)
+    }
+}
+
+impl Future for ReusableWaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.get_mut().wg).poll(cx)
+    }
+}
+
+impl<T> Future for MonoValueWaitGroup<T> {
+    type Output = Option<T>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        ready!(this.0.poll_done(cx));
+        Poll::Ready(unsafe { this.0.take() })
+    }
+}
+
+impl Future for SharedWaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        unsafe { this.waiter.poll_shared(cx, Some(&mut this.key)) }
+    }
+}
+
+impl Future for WaitShared<'_> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        unsafe { this.wg.waiter.poll_shared(cx, Some(&mut this.key)) }
+    }
+}
+
+impl<W: Future<Output = ()> + Unpin, T: Future<Output = ()>> Future for WithTimeout<W, T> {
+    type Output = Result<(), TimedOut<W>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let inner = this
+            .inner
+            .as_mut()
+            .expect("`WithTimeout` polled after completion");
+        if Pin::new(inner).poll(cx).is_ready() {
+            *this.inner = None;
+            return Poll::Ready(Ok(()));
+        }
+        ready!(this.timer.poll(cx));
+        Poll::Ready(Err(TimedOut(this.inner.take().unwrap())))
+    }
+}
+
+impl<W: private::Waitable> Future for Wait<'_, W> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.wg.poll_wait(cx, &mut this.key)
+    }
+}
+
+impl<W: private::Waitable> Drop for Wait<'_, W> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.wg.remove_waker(key);
+        }
+    }
+}
+
+impl private::Waitable for WaitGroup {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.0.poll_shared(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.0.remove_waker(key);
+    }
+}
+
+impl private::Waitable for MonoWaitGroup {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.0.poll_shared(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.0.remove_waker(key);
+    }
+}
+
+impl private::Waitable for CounterWaitGroup {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.poll_keyed(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.inner.remove_waker(key);
+    }
+}
+
+impl<'a, T> WaitAbandoned<'a, T> {
+    #[inline]
+    fn new(layout: &'a crate::layout::MonoLayout) -> Self {
+        Self {
+            layout,
+            key: None,
+            _token: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> Future for WaitAbandoned<'_, T> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.layout.poll_abandoned(cx, Some(&mut this.key))
+    }
+}
+
+impl<T> Drop for WaitAbandoned<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.layout.remove_abandon_waker(key);
+        }
+    }
+}
+
+impl GroupTokenFactory {
+    /// Consumes the inner token.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Extracts the inner [`GroupToken`].
+    #[inline]
+    pub fn into_token(self) -> GroupToken {
+        self.0
+    }
+
+    /// Executes a closure with the inner [`GroupToken`].
+    #[inline]
+    pub fn scope<T, F: FnOnce(GroupToken) -> T>(self, func: F) -> T {
+        func(self.into_token())
+    }
+}
+
+impl StartupGroupTokenFactory {
+    /// Creates a new `StartupGroupTokenFactory`, with the "started" and the
+    /// "finished" [`WaitGroup`]s.
+    pub fn new() -> (WaitGroup, WaitGroup, Self) {
+        let (started_wg, started) = WaitGroup::new();
+        let (finished_wg, finished) = WaitGroup::new();
+        let factory = Self {
+            started: started.into_token(),
+            finished: finished.into_token(),
+        };
+        (started_wg, finished_wg, factory)
+    }
+
+    /// Consumes the inner tokens.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns a pair of "started" and "finished" [`GroupToken`]s for a new
+    /// task.
+    #[inline]
+    pub fn tokens(&self) -> (GroupToken, GroupToken) {
+        (self.started.clone(), self.finished.clone())
+    }
+
+    /// Extracts the inner "started" and "finished" [`GroupToken`]s.
+    #[inline]
+    pub fn into_tokens(self) -> (GroupToken, GroupToken) {
+        (self.started, self.finished)
+    }
+
+    /// Wraps a future with a new pair of tokens.
+    ///
+    /// The "started" token is released on the first poll, and the
+    /// "finished" token once the future is ready.
+    #[inline]
+    pub fn track<F: Future>(&self, fut: F) -> StartupTracked<F> {
+        let (started, finished) = self.tokens();
+        fut.release_on_start(started).release_on_ready(finished)
+    }
+}
+
+impl SharedGroupTokenFactory {
+    /// Consumes the inner token.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Extracts the inner [`SharedGroupToken`].
+    #[inline]
+    pub fn into_token(self) -> SharedGroupToken {
+        self.0
+    }
+
+    /// Executes a closure with the inner [`SharedGroupToken`].
+    #[inline]
+    pub fn scope<T, F: FnOnce(SharedGroupToken) -> T>(self, func: F) -> T {
+        func(self.into_token())
+    }
+}
+
+impl GroupToken {
+    #[inline]
+    fn new(token: ClonableTwinRef<SharedLayout>) -> Self {
+        #[cfg(feature = "stats")]
+        let lifetime = crate::stats::TokenLifetime::start(token.stats());
+        Self(
+            token,
+            #[cfg(feature = "stats")]
+            lifetime,
+        )
+    }
+
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns the number of [`GroupToken`]s of the group still alive,
+    /// including this one.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.0.outstanding()
+    }
+
+    /// Creates a child [`WaitGroup`] and a [`GroupTokenFactory`].
+    ///
+    /// The child group holds a clone of this token until all of its own
+    /// tokens have been released, so that waiting for this group also waits
+    /// for the child group.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::WaitGroup;
+    /// # futures_executor::block_on(async {
+    /// let (wg, factory) = WaitGroup::new();
+    /// let (child_wg, child_factory) = factory.scope(|token| token.child());
+    /// assert!(!wg.is_done());
+    ///
+    /// child_factory.release();
+    /// child_wg.await;
+    /// wg.await;
+    /// # });
+    /// ```
+    pub fn child(&self) -> (WaitGroup, GroupTokenFactory) {
+        let inner = NestedLayout::new(self.clone());
+        let (wg, token) = unsafe { TwinRef::new_clonable_in(inner) };
+        (
+            WaitGroup(WaitGroupWrapper::new(wg)),
+            GroupTokenFactory(GroupToken::new(token)),
+        )
+    }
+
+    /// Converts the token into a [`Waker`] that releases it when woken.
+    ///
+    /// The token is released by the first wake of any clone of the waker, by
+    /// value or by reference, and later wakes do nothing. If no clone is
+    /// woken, the token is released once every clone has been dropped. Unlike
+    /// [`MonoGroupToken::into_waker`], the clones share the token in a small
+    /// allocation.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::WaitGroup;
+    /// let (wg, factory) = WaitGroup::new();
+    /// let waker = factory.into_token().into_waker();
+    /// let waker_cloned = waker.clone();
+    ///
+    /// waker_cloned.wake_by_ref();
+    /// assert!(wg.is_done());
+    /// waker.wake();
+    /// drop(waker_cloned);
+    /// ```
+    #[inline]
+    pub fn into_waker(self) -> Waker {
+        crate::waker::token_waker(self)
+    }
+
+    /// Checks if the [`WaitGroup`] has been dropped.
+    ///
+    /// Once abandoned, nobody is waiting for the group anymore.
+    #[inline]
+    pub fn is_abandoned(&self) -> bool {
+        self.0.is_abandoned()
+    }
+
+    /// Polls for the [`WaitGroup`] to be dropped, registering the waker of
+    /// `cx`.
+    ///
+    /// The wakers registered here are only deduplicated, and kept until the
+    /// group is abandoned or completes. Awaiting [`abandoned`](Self::abandoned)
+    /// unregisters the waker once the future is dropped instead.
+    #[inline]
+    pub fn poll_abandoned(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.abandon_layout().poll_abandoned(cx, None)
+    }
+
+    /// Waits for the [`WaitGroup`] to be dropped.
+    #[inline]
+    pub fn abandoned(&self) -> WaitAbandoned<'_, Self> {
+        WaitAbandoned::new(self.abandon_layout())
+    }
+
+    #[inline]
+    pub(crate) fn abandon_layout(&self) -> &crate::layout::MonoLayout {
+        let layout: &SharedLayout = &self.0;
+        core::borrow::Borrow::borrow(layout)
+    }
+}
+
+impl SharedGroupToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+}
+
+impl MonoGroupToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Converts the token into a [`Waker`] that releases it when woken.
+    ///
+    /// The token is released as with [`GroupToken::into_waker`]. The waker
+    /// points to the group itself, without any extra allocation.
+    ///
+    /// ```rust
+    /// # use compact_waitgroup::MonoWaitGroup;
+    /// let (wg, token) = MonoWaitGroup::new();
+    /// let waker = token.into_waker();
+    /// let waker_cloned = waker.clone();
+    ///
+    /// waker_cloned.wake();
+    /// assert!(wg.is_done());
+    /// waker.wake();
+    /// ```
+    #[inline]
+    pub fn into_waker(self) -> Waker {
+        let this = core::mem::ManuallyDrop::new(self);
+        crate::waker::mono_waker(unsafe { core::ptr::read(&this.0) })
+    }
+
+    /// Checks if the [`MonoWaitGroup`] has been dropped.
+    ///
+    /// Once abandoned, nobody is waiting for the group anymore.
+    #[inline]
+    pub fn is_abandoned(&self) -> bool {
+        self.0.is_abandoned()
+    }
+
+    /// Polls for the [`MonoWaitGroup`] to be dropped, registering the waker
+    /// of `cx`.
+    ///
+    /// The wakers registered here are only deduplicated, and kept until the
+    /// group is abandoned or completes. Awaiting [`abandoned`](Self::abandoned)
+    /// unregisters the waker once the future is dropped instead.
+    #[inline]
+    pub fn poll_abandoned(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.abandon_layout().poll_abandoned(cx, None)
+    }
+
+    /// Waits for the [`MonoWaitGroup`] to be dropped.
+    #[inline]
+    pub fn abandoned(&self) -> WaitAbandoned<'_, Self> {
+        WaitAbandoned::new(self.abandon_layout())
+    }
+
+    #[inline]
+    pub(crate) fn abandon_layout(&self) -> &crate::layout::MonoLayout {
+        let layout: &MonoLayout = &self.0;
+        core::borrow::Borrow::borrow(layout)
+    }
+
+    /// Returns the token itself.
     ///
     /// Provided for API consistency with [`GroupTokenFactory`].
     #[inline]
@@ -220,6 +1464,72 @@ impl MonoGroupToken {
     }
 }
 
+impl<T> MonoValueGroupToken<T> {
+    /// Sends the value and consumes the token.
+    #[inline]
+    pub fn complete(self, value: T) {
+        unsafe {
+            self.0.put(value);
+        }
+    }
+
+    /// Consumes the token without a value.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+}
+
+impl<T> Drop for MonoValueGroupToken<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.0.send_done();
+        }
+    }
+}
+
+impl Clone for GroupToken {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+#[cfg(feature = "stats")]
+impl Drop for GroupToken {
+    #[inline]
+    fn drop(&mut self) {
+        self.1.end(self.0.stats());
+    }
+}
+
+impl Clone for SharedWaitGroup {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            waiter: self.waiter.clone(),
+            key: None,
+        }
+    }
+}
+
+impl Drop for SharedWaitGroup {
+    #[inline]
+    fn drop(&mut self) {
+        self.remove_waker(self.key);
+    }
+}
+
+impl Drop for WaitShared<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.wg.remove_waker(self.key);
+    }
+}
+
 impl Drop for MonoGroupToken {
     #[inline]
     fn drop(&mut self) {
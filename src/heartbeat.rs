@@ -0,0 +1,267 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+use derive_more::{Debug, Into};
+
+use crate::{
+    group::{Wait, private::Waitable},
+    layout::ReportLayout,
+    sync::{WaitGroupLayoutExt, WaitGroupWrapper},
+    twin_ref::{ClonableTwinRef, TwinRef},
+};
+
+#[derive(Debug, Default)]
+struct Heartbeats {
+    next_id: usize,
+    last: BTreeMap<usize, Instant>,
+}
+
+impl Heartbeats {
+    #[inline]
+    fn register(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.last.insert(id, Instant::now());
+        id
+    }
+}
+
+type HeartbeatLayout = ReportLayout<Heartbeats>;
+
+/// WaitGroup with a stall detector.
+///
+/// Each [`HeartbeatGroupToken`] has its own id, and records the time of its
+/// last [`heartbeat`](HeartbeatGroupToken::heartbeat). The waiter reports the
+/// tokens whose last heartbeat is older than a threshold with
+/// [`stalled`](Self::stalled), without any background thread.
+///
+/// A token records a heartbeat when it is created, including by cloning.
+///
+/// [`WaitGroup`](crate::WaitGroup) itself has no stall detector, since its
+/// tokens are a bare reference count: telling them apart takes an id per
+/// token, and a locked map updated on every clone and drop. This type only
+/// adds that cost where stalls are worth reporting.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use compact_waitgroup::HeartbeatWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (wg, factory) = HeartbeatWaitGroup::new();
+/// let token = factory.into_token();
+/// let token_cloned = token.clone();
+///
+/// std::thread::sleep(Duration::from_millis(20));
+/// token.heartbeat();
+///
+/// let stalled = wg.stalled(Duration::from_millis(10));
+/// assert_eq!(stalled.len(), 1);
+/// assert_eq!(stalled[0].id, token_cloned.id());
+///
+/// drop((token, token_cloned));
+/// wg.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct HeartbeatWaitGroup(
+    #[debug("done: {}, outstanding: {}", _0.is_done(), _0.outstanding())]
+    WaitGroupWrapper<TwinRef<HeartbeatLayout>>,
+);
+
+/// Factory of [`HeartbeatGroupToken`].
+///
+/// Provides methods to obtain or scope the clonable token for distribution.
+#[must_use]
+#[derive(Debug, Into)]
+pub struct HeartbeatGroupTokenFactory(HeartbeatGroupToken);
+
+/// Clonable group token of a [`HeartbeatWaitGroup`] with heartbeats.
+///
+/// Each clone has its own id and heartbeat.
+#[must_use]
+#[derive(Debug)]
+pub struct HeartbeatGroupToken {
+    #[debug(skip)]
+    token: ClonableTwinRef<HeartbeatLayout>,
+    id: usize,
+}
+
+/// Token reported by [`HeartbeatWaitGroup::stalled`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StalledToken {
+    /// Id of the token, as returned by [`HeartbeatGroupToken::id`].
+    pub id: usize,
+    /// Time elapsed since the last heartbeat of the token.
+    pub elapsed: Duration,
+}
+
+impl HeartbeatWaitGroup {
+    /// Creates a new `HeartbeatWaitGroup` and a [`HeartbeatGroupTokenFactory`].
+    pub fn new() -> (Self, HeartbeatGroupTokenFactory) {
+        let mut heartbeats = Heartbeats::default();
+        let id = heartbeats.register();
+        let (wg, token) = TwinRef::new_clonable(ReportLayout::new(heartbeats));
+        (
+            Self(WaitGroupWrapper::new(wg)),
+            HeartbeatGroupTokenFactory(HeartbeatGroupToken { token, id }),
+        )
+    }
+
+    /// Checks if the `HeartbeatWaitGroup` has completed.
+    ///
+    /// This returns `true` if all [`HeartbeatGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+
+    /// Returns the number of [`HeartbeatGroupToken`]s still alive.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        if self.is_done() {
+            0
+        } else {
+            self.0.outstanding()
+        }
+    }
+
+    /// Returns the tokens whose last heartbeat is at least `threshold` old,
+    /// ordered by id.
+    pub fn stalled(&self, threshold: Duration) -> Vec<StalledToken> {
+        let now = Instant::now();
+        self.0.report().with(|heartbeats| {
+            heartbeats
+                .last
+                .iter()
+                .map(|(&id, &last)| StalledToken {
+                    id,
+                    elapsed: now.saturating_duration_since(last),
+                })
+                .filter(|token| token.elapsed >= threshold)
+                .collect()
+        })
+    }
+
+    /// Polls the `HeartbeatWaitGroup` for completion through a shared
+    /// reference.
+    ///
+    /// Registers the waker as
+    /// [`WaitGroup::poll_done`](crate::WaitGroup::poll_done) does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_shared(cx, None)
+    }
+
+    /// Returns a future that waits for the `HeartbeatWaitGroup` through a
+    /// shared reference.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+}
+
+impl HeartbeatGroupTokenFactory {
+    /// Consumes the factory and releases the group token.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Consumes the factory and returns the inner token.
+    #[inline]
+    pub fn into_token(self) -> HeartbeatGroupToken {
+        self.0
+    }
+
+    /// Executes a closure with the inner token.
+    #[inline]
+    pub fn scope<T, F: FnOnce(HeartbeatGroupToken) -> T>(self, func: F) -> T {
+        func(self.into_token())
+    }
+}
+
+impl HeartbeatGroupToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns the id of the token, unique within its group.
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Records a heartbeat of the token.
+    pub fn heartbeat(&self) {
+        let now = Instant::now();
+        self.token.report().with(|heartbeats| {
+            if let Some(last) = heartbeats.last.get_mut(&self.id) {
+                *last = now;
+            }
+        });
+    }
+}
+
+impl Clone for HeartbeatGroupToken {
+    #[inline]
+    fn clone(&self) -> Self {
+        let token = self.token.clone();
+        let id = token.report().with(Heartbeats::register);
+        Self { token, id }
+    }
+}
+
+impl Drop for HeartbeatGroupToken {
+    #[inline]
+    fn drop(&mut self) {
+        self.token.report().with(|heartbeats| {
+            heartbeats.last.remove(&self.id);
+        });
+    }
+}
+
+impl Future for HeartbeatWaitGroup {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_done(cx)
+    }
+}
+
+impl Waitable for HeartbeatWaitGroup {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.0.poll_shared(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.0.remove_waker(key);
+    }
+}
+
+impl fmt::Display for StalledToken {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "token {} stalled for {:?}", self.id, self.elapsed)
+    }
+}
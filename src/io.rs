@@ -0,0 +1,70 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use std::io;
+
+use derive_more::Into;
+use futures_io::AsyncRead;
+use pin_project_lite::pin_project;
+
+use crate::ext::GroupTokenType;
+
+/// Extension trait for async readers to automatically release group tokens.
+pub trait GroupTokenAsyncReadExt<T>: Sized {
+    /// Releases the group token when the reader reaches EOF or is dropped.
+    ///
+    /// EOF is reached once a read into a non-empty buffer returns `Ok(0)`.
+    #[inline]
+    fn release_on_eof(self, token: T) -> GroupTokenReleaseOnEof<Self, T> {
+        GroupTokenReleaseOnEof {
+            inner: self,
+            token: Some(token),
+        }
+    }
+}
+
+impl<T: GroupTokenType, R: AsyncRead> GroupTokenAsyncReadExt<T> for R {}
+
+pin_project! {
+    /// Wrapper that releases a token when the reader reaches EOF or is
+    /// dropped.
+    ///
+    /// Created by [`GroupTokenAsyncReadExt::release_on_eof`].
+    #[derive(Debug, Into)]
+    pub struct GroupTokenReleaseOnEof<R, T> {
+        #[pin]
+        inner: R,
+        token: Option<T>,
+    }
+}
+
+impl<R, T> GroupTokenReleaseOnEof<R, T> {
+    /// Returns a pinned mutable reference to the inner reader.
+    #[inline]
+    pub fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Returns a reference to the associated token if not yet released.
+    #[inline]
+    pub fn group_token(&self) -> Option<&T> {
+        self.token.as_ref()
+    }
+}
+
+impl<R: AsyncRead, T> AsyncRead for GroupTokenReleaseOnEof<R, T> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let res = ready!(this.inner.poll_read(cx, buf));
+        if matches!(res, Ok(0)) && !buf.is_empty() {
+            drop(this.token.take());
+        }
+        Poll::Ready(res)
+    }
+}
@@ -0,0 +1,295 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use derive_more::Debug;
+
+use crate::{
+    group::{Wait, WithTimeout, private::Waitable},
+    layout::ReportLayout,
+    sync::{WaitGroupLayoutExt, WaitGroupWrapper},
+    twin_ref::{ClonableTwinRef, TwinRef},
+};
+
+#[derive(Debug)]
+struct Labels<K> {
+    next_id: usize,
+    factory: bool,
+    labels: BTreeMap<usize, K>,
+}
+
+impl<K> Labels<K> {
+    #[inline]
+    fn register(&mut self, label: K) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.labels.insert(id, label);
+        id
+    }
+}
+
+type LabeledLayout<K> = ReportLayout<Labels<K>>;
+
+/// WaitGroup with labeled group tokens.
+///
+/// Each [`LabeledGroupToken`] is tagged with a label, such as a task name or
+/// a shard id, when handed out by the [`LabeledGroupTokenFactory`]. The
+/// waiter reports the labels of the tokens still alive with
+/// [`outstanding_labels`](Self::outstanding_labels) and
+/// [`waiting_on`](Self::waiting_on), which is useful to log on a timeout.
+///
+/// The group completes once the factory and every token have been dropped.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// ```rust
+/// # use compact_waitgroup::LabeledWaitGroup;
+/// # futures_executor::block_on(async {
+/// let (wg, factory) = LabeledWaitGroup::new();
+/// let shards = [7, 12, 13].map(|id| factory.token(format!("shard-{id}")));
+/// factory.release();
+///
+/// let [shard_7, shard_12, shard_13] = shards;
+/// shard_13.release();
+///
+/// let timed_out = wg.with_timeout(async {}).await.unwrap_err();
+/// let wg = timed_out.into_inner();
+/// assert_eq!(wg.waiting_on().to_string(), "waiting on shard-7, shard-12");
+///
+/// drop((shard_7, shard_12));
+/// wg.await;
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct LabeledWaitGroup<K>(
+    #[debug("done: {}", _0.is_done())] WaitGroupWrapper<TwinRef<LabeledLayout<K>>>,
+);
+
+/// Factory of [`LabeledGroupToken`].
+///
+/// Keeps the [`LabeledWaitGroup`] from completing until released.
+#[must_use]
+pub struct LabeledGroupTokenFactory<K>(ClonableTwinRef<LabeledLayout<K>>);
+
+/// Group token of a [`LabeledWaitGroup`] tagged with a label.
+///
+/// Cloning the token also clones its label.
+#[must_use]
+pub struct LabeledGroupToken<K> {
+    token: ClonableTwinRef<LabeledLayout<K>>,
+    id: usize,
+}
+
+/// What a [`LabeledWaitGroup`] is waiting on.
+///
+/// Created by [`LabeledWaitGroup::waiting_on`]. Displayed as a list of the
+/// labels, such as `waiting on shard-7, shard-12`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitingOn<K> {
+    /// Labels of the tokens still alive, ordered by creation.
+    pub labels: Vec<K>,
+    /// Whether the [`LabeledGroupTokenFactory`] is still alive.
+    pub factory: bool,
+}
+
+impl<K> LabeledWaitGroup<K> {
+    /// Creates a new `LabeledWaitGroup` and a [`LabeledGroupTokenFactory`].
+    pub fn new() -> (Self, LabeledGroupTokenFactory<K>) {
+        let labels = Labels {
+            next_id: 0,
+            factory: true,
+            labels: BTreeMap::new(),
+        };
+        let (wg, token) = TwinRef::new_clonable(ReportLayout::new(labels));
+        (
+            Self(WaitGroupWrapper::new(wg)),
+            LabeledGroupTokenFactory(token),
+        )
+    }
+
+    /// Checks if the `LabeledWaitGroup` has completed.
+    ///
+    /// This returns `true` if the [`LabeledGroupTokenFactory`] and all
+    /// [`LabeledGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+
+    /// Returns the labels of the [`LabeledGroupToken`]s still alive, ordered
+    /// by creation.
+    pub fn outstanding_labels(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.0
+            .report()
+            .with(|labels| labels.labels.values().cloned().collect())
+    }
+
+    /// Returns what the `LabeledWaitGroup` is waiting on, to be displayed or
+    /// logged.
+    pub fn waiting_on(&self) -> WaitingOn<K>
+    where
+        K: Clone,
+    {
+        self.0.report().with(|labels| WaitingOn {
+            labels: labels.labels.values().cloned().collect(),
+            factory: labels.factory,
+        })
+    }
+
+    /// Polls the `LabeledWaitGroup` for completion through a shared
+    /// reference.
+    ///
+    /// Registers the waker as
+    /// [`WaitGroup::poll_done`](crate::WaitGroup::poll_done) does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_shared(cx, None)
+    }
+
+    /// Returns a future that waits for the `LabeledWaitGroup` through a
+    /// shared reference.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+
+    /// Waits for the `LabeledWaitGroup` until `timer` is ready.
+    ///
+    /// Resolves to [`TimedOut`](crate::TimedOut) holding the
+    /// `LabeledWaitGroup` if the timer fires first, which can then report
+    /// what it is [`waiting_on`](Self::waiting_on).
+    #[inline]
+    pub fn with_timeout<T: Future<Output = ()>>(self, timer: T) -> WithTimeout<Self, T> {
+        WithTimeout::new(self, timer)
+    }
+}
+
+impl<K> LabeledGroupTokenFactory<K> {
+    /// Consumes the factory.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns a new [`LabeledGroupToken`] tagged with `label`.
+    pub fn token(&self, label: K) -> LabeledGroupToken<K> {
+        let token = self.0.clone();
+        let id = token.report().with(|labels| labels.register(label));
+        LabeledGroupToken { token, id }
+    }
+}
+
+impl<K> LabeledGroupToken<K> {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Returns the label of the token.
+    pub fn label(&self) -> K
+    where
+        K: Clone,
+    {
+        self.token
+            .report()
+            .with(|labels| labels.labels[&self.id].clone())
+    }
+}
+
+impl<K: Clone> Clone for LabeledGroupToken<K> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let token = self.token.clone();
+        let id = token.report().with(|labels| {
+            let label = labels.labels[&self.id].clone();
+            labels.register(label)
+        });
+        Self { token, id }
+    }
+}
+
+impl<K> Drop for LabeledGroupTokenFactory<K> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.report().with(|labels| labels.factory = false);
+    }
+}
+
+impl<K> Drop for LabeledGroupToken<K> {
+    #[inline]
+    fn drop(&mut self) {
+        // The label is dropped outside of the lock.
+        let label = self
+            .token
+            .report()
+            .with(|labels| labels.labels.remove(&self.id));
+        drop(label);
+    }
+}
+
+impl<K> Future for LabeledWaitGroup<K> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().0.poll_done(cx)
+    }
+}
+
+impl<K> Waitable for LabeledWaitGroup<K> {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.0.poll_shared(cx, Some(key))
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.0.remove_waker(key);
+    }
+}
+
+impl<K> fmt::Debug for LabeledGroupTokenFactory<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LabeledGroupTokenFactory")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K> fmt::Debug for LabeledGroupToken<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LabeledGroupToken").finish_non_exhaustive()
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for WaitingOn<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("waiting on ")?;
+        if self.labels.is_empty() && !self.factory {
+            return f.write_str("nothing");
+        }
+        let mut sep = "";
+        for label in &self.labels {
+            write!(f, "{sep}{label}")?;
+            sep = ", ";
+        }
+        if self.factory {
+            write!(f, "{sep}the factory")?;
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
 use core::{
     borrow::Borrow,
     panic::{RefUnwindSafe, UnwindSafe},
+    ptr::NonNull,
 };
 
+use alloc::boxed::Box;
+
 use derive_more::Deref;
 
+#[cfg(feature = "stats")]
+use crate::stats::StatsRecorder;
 use crate::{
-    sync::{WaitGroupData, WaitGroupLayout, WaitGroupLayoutExt},
+    group::GroupToken,
+    sync::{
+        MultiWaitGroupLayout, MultiWaitGroupLayoutExt, NESTED, SpinLock, WaitGroupData,
+        WaitGroupLayout, WaitGroupLayoutExt, WakerSlab, with_cell_mut,
+    },
     twin_ref::{ClonableTwinRefLayout, TwinRef, TwinRefLayout},
     utils::*,
 };
@@ -15,12 +24,15 @@ use crate::{
 pub(crate) struct MonoLayout {
     twin_count: AtomicU8,
     state: AtomicU8,
+    /// Number of clones of the waker made of the token side, if any, and
+    /// whether one of them has been woken.
+    waker_count: AtomicU32,
     data: UnsafeCell<WaitGroupData>,
 }
 
 #[cfg(not(loom))]
 const _: () = {
-    assert!(core::mem::size_of::<MonoLayout>() == core::mem::size_of::<usize>() * 3);
+    assert!(core::mem::size_of::<MonoLayout>() == core::mem::size_of::<usize>() * 4);
     assert!(core::mem::align_of::<MonoLayout>() == core::mem::size_of::<usize>());
 };
 
@@ -35,9 +47,15 @@ impl MonoLayout {
         Self {
             twin_count: AtomicU8::new(2),
             state: AtomicU8::new(0),
-            data: UnsafeCell::new(WaitGroupData::None),
+            waker_count: AtomicU32::new(0),
+            data: UnsafeCell::new(WaitGroupData::new()),
         }
     }
+
+    #[inline]
+    pub fn waker_count(&self) -> &AtomicU32 {
+        &self.waker_count
+    }
 }
 
 #[derive(Debug, Deref)]
@@ -45,11 +63,13 @@ pub(crate) struct SharedLayout {
     cloned_count: AtomicUsize,
     #[deref]
     inner: MonoLayout,
+    #[cfg(feature = "stats")]
+    stats: StatsRecorder,
 }
 
-#[cfg(not(loom))]
+#[cfg(all(not(loom), not(feature = "stats")))]
 const _: () = {
-    assert!(core::mem::size_of::<SharedLayout>() == core::mem::size_of::<usize>() * 4);
+    assert!(core::mem::size_of::<SharedLayout>() == core::mem::size_of::<usize>() * 5);
     assert!(core::mem::align_of::<SharedLayout>() == core::mem::size_of::<usize>());
 };
 
@@ -59,8 +79,22 @@ impl SharedLayout {
         Self {
             cloned_count: AtomicUsize::new(1),
             inner: MonoLayout::new(),
+            #[cfg(feature = "stats")]
+            stats: StatsRecorder::default(),
         }
     }
+
+    /// Returns the number of clonable twins still alive.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.cloned_count.load(atomic::Relaxed)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> &StatsRecorder {
+        &self.stats
+    }
 }
 
 impl Borrow<MonoLayout> for SharedLayout {
@@ -77,10 +111,160 @@ impl Borrow<MonoLayout> for TwinRef<SharedLayout> {
     }
 }
 
-unsafe impl<T: Borrow<MonoLayout>> TwinRefLayout for T {
+/// [`SharedLayout`] of a child group, holding a token of its parent group.
+///
+/// Allocated as a whole, but referred to as its inner [`SharedLayout`] marked
+/// as nested. The parent token is released once the child group completes.
+#[repr(C)]
+pub(crate) struct NestedLayout {
+    inner: SharedLayout,
+    parent: UnsafeCell<Option<GroupToken>>,
+}
+
+impl NestedLayout {
+    #[inline]
+    pub fn new(parent: GroupToken) -> Self {
+        let mut inner = SharedLayout::new();
+        inner.inner.state = AtomicU8::new(NESTED);
+        Self {
+            inner,
+            parent: UnsafeCell::new(Some(parent)),
+        }
+    }
+}
+
+/// [`MonoLayout`] with a value sent by the single group token.
+#[derive(Debug, Deref)]
+pub(crate) struct ValueLayout<T> {
+    #[deref]
+    inner: MonoLayout,
+    value: UnsafeCell<Option<T>>,
+}
+
+unsafe impl<T: Send> Send for ValueLayout<T> {}
+unsafe impl<T: Send> Sync for ValueLayout<T> {}
+impl<T: UnwindSafe> UnwindSafe for ValueLayout<T> {}
+impl<T: UnwindSafe> RefUnwindSafe for ValueLayout<T> {}
+
+impl<T> ValueLayout<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: MonoLayout::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the token side, before sending done.
+    #[inline]
+    pub unsafe fn put(&self, value: T) {
+        unsafe { with_cell_mut(&self.value, |slot| *slot = Some(value)) }
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the waiter side, after done is received.
+    #[inline]
+    pub unsafe fn take(&self) -> Option<T> {
+        unsafe { with_cell_mut(&self.value, Option::take) }
+    }
+}
+
+impl<T> Borrow<MonoLayout> for ValueLayout<T> {
+    #[inline]
+    fn borrow(&self) -> &MonoLayout {
+        self
+    }
+}
+
+impl<T> Borrow<MonoLayout> for TwinRef<ValueLayout<T>> {
+    #[inline]
+    fn borrow(&self) -> &MonoLayout {
+        self
+    }
+}
+
+/// [`SharedLayout`] with extra data reported by the group tokens.
+#[derive(Debug, Deref)]
+pub(crate) struct ReportLayout<D> {
+    #[deref]
+    inner: SharedLayout,
+    report: SpinLock<D>,
+}
+
+impl<D> ReportLayout<D> {
+    #[inline]
+    pub fn new(report: D) -> Self {
+        Self {
+            inner: SharedLayout::new(),
+            report: SpinLock::new(report),
+        }
+    }
+
+    #[inline]
+    pub fn report(&self) -> &SpinLock<D> {
+        &self.report
+    }
+}
+
+impl<D> Borrow<SharedLayout> for ReportLayout<D> {
+    #[inline]
+    fn borrow(&self) -> &SharedLayout {
+        self
+    }
+}
+
+impl<D> Borrow<MonoLayout> for ReportLayout<D> {
+    #[inline]
+    fn borrow(&self) -> &MonoLayout {
+        self
+    }
+}
+
+impl<D> Borrow<MonoLayout> for TwinRef<ReportLayout<D>> {
+    #[inline]
+    fn borrow(&self) -> &MonoLayout {
+        self
+    }
+}
+
+unsafe impl TwinRefLayout for MonoLayout {
+    #[inline]
+    fn count(&self) -> &AtomicU8 {
+        &self.twin_count
+    }
+}
+
+unsafe impl TwinRefLayout for SharedLayout {
+    #[inline]
+    fn count(&self) -> &AtomicU8 {
+        &self.inner.twin_count
+    }
+
+    #[inline]
+    unsafe fn dealloc(this: NonNull<Self>) {
+        if unsafe { this.as_ref() }.is_nested() {
+            // Only the `SharedLayout` of a `NestedLayout` is marked as nested.
+            drop(unsafe { Box::from_raw(this.cast::<NestedLayout>().as_ptr()) });
+        } else {
+            drop(unsafe { Box::from_raw(this.as_ptr()) });
+        }
+    }
+}
+
+unsafe impl<T> TwinRefLayout for ValueLayout<T> {
     #[inline]
     fn count(&self) -> &AtomicU8 {
-        &self.borrow().twin_count
+        &self.inner.twin_count
+    }
+}
+
+unsafe impl<D> TwinRefLayout for ReportLayout<D> {
+    #[inline]
+    fn count(&self) -> &AtomicU8 {
+        &self.inner.inner.twin_count
     }
 }
 
@@ -96,16 +280,134 @@ unsafe impl<T: Borrow<MonoLayout>> WaitGroupLayout for T {
     }
 }
 
-unsafe impl<T: Borrow<SharedLayout>> ClonableTwinRefLayout for T {
+unsafe impl ClonableTwinRefLayout for SharedLayout {
+    #[inline]
+    fn cloned_count(&self) -> &AtomicUsize {
+        &self.cloned_count
+    }
+
+    #[inline]
+    fn action_on_zero(&self) {
+        unsafe {
+            self.send_done();
+        }
+    }
+
+    #[inline]
+    unsafe fn action_on_zero_raw(this: NonNull<Self>) {
+        let layout = unsafe { this.as_ref() };
+        unsafe {
+            layout.send_done();
+        }
+        if layout.is_nested() {
+            // Only the `SharedLayout` of a `NestedLayout` is marked as nested.
+            let nested = this.cast::<NestedLayout>().as_ptr();
+            drop(unsafe { with_cell_mut(&(*nested).parent, Option::take) });
+        }
+    }
+}
+
+unsafe impl<D> ClonableTwinRefLayout for ReportLayout<D> {
+    #[inline]
+    fn cloned_count(&self) -> &AtomicUsize {
+        &self.inner.cloned_count
+    }
+
+    #[inline]
+    fn action_on_zero(&self) {
+        unsafe {
+            self.inner.send_done();
+        }
+    }
+}
+
+/// Marker for the clonable waiter side of [`MultiLayout`].
+#[derive(Debug)]
+pub(crate) struct WaiterSide;
+
+#[derive(Debug)]
+pub(crate) struct MultiLayout {
+    cloned_count: AtomicUsize,
+    waiter_count: AtomicUsize,
+    twin_count: AtomicU8,
+    state: AtomicU8,
+    wakers: UnsafeCell<WakerSlab>,
+}
+
+#[cfg(not(loom))]
+const _: () = {
+    assert!(core::mem::size_of::<MultiLayout>() == core::mem::size_of::<usize>() * 6);
+    assert!(core::mem::align_of::<MultiLayout>() == core::mem::size_of::<usize>());
+};
+
+unsafe impl Send for MultiLayout {}
+unsafe impl Sync for MultiLayout {}
+impl UnwindSafe for MultiLayout {}
+impl RefUnwindSafe for MultiLayout {}
+
+impl MultiLayout {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cloned_count: AtomicUsize::new(1),
+            waiter_count: AtomicUsize::new(1),
+            twin_count: AtomicU8::new(2),
+            state: AtomicU8::new(0),
+            wakers: UnsafeCell::new(WakerSlab::new()),
+        }
+    }
+
+    /// Returns the number of token side twins still alive.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.cloned_count.load(atomic::Relaxed)
+    }
+}
+
+unsafe impl TwinRefLayout for MultiLayout {
+    #[inline]
+    fn count(&self) -> &AtomicU8 {
+        &self.twin_count
+    }
+}
+
+unsafe impl MultiWaitGroupLayout for MultiLayout {
+    #[inline]
+    fn state(&self) -> &AtomicU8 {
+        &self.state
+    }
+
+    #[inline]
+    unsafe fn wakers(&self) -> &UnsafeCell<WakerSlab> {
+        &self.wakers
+    }
+}
+
+unsafe impl ClonableTwinRefLayout for MultiLayout {
+    #[inline]
+    fn cloned_count(&self) -> &AtomicUsize {
+        &self.cloned_count
+    }
+
+    #[inline]
+    fn action_on_zero(&self) {
+        unsafe {
+            self.send_done();
+        }
+    }
+}
+
+unsafe impl ClonableTwinRefLayout<WaiterSide> for MultiLayout {
     #[inline]
+    #[allow(clippy::misnamed_getters)]
     fn cloned_count(&self) -> &AtomicUsize {
-        &self.borrow().cloned_count
+        &self.waiter_count
     }
 
     #[inline]
     fn action_on_zero(&self) {
         unsafe {
-            self.borrow().send_done();
+            self.drop_wakers();
         }
     }
 }
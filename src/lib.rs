@@ -50,6 +50,32 @@
 //! # });
 //! ```
 //!
+//! ## [`SharedWaitGroup`]
+//!
+//! ```rust
+//! use compact_waitgroup::SharedWaitGroup;
+//!
+//! let (wg, factory) = SharedWaitGroup::new();
+//! let wg_cloned = wg.clone();
+//!
+//! std::thread::spawn(move || {
+//!     // Another waiter
+//! # futures_executor::block_on(async {
+//!     wg_cloned.await;
+//! # });
+//! });
+//!
+//! std::thread::spawn(move || {
+//!     // Long-running task
+//!     factory.release();
+//! });
+//!
+//! // Wait for the task to complete
+//! # futures_executor::block_on(async {
+//! wg.await;
+//! # });
+//! ```
+//!
 //! ## With `async` Runtime
 //!
 //! ```rust
@@ -75,6 +101,33 @@
 //! # });
 //! ```
 //!
+//! ## Blocking
+//!
+//! With the `std` feature enabled, [`WaitGroup`] and [`MonoWaitGroup`] can
+//! also be waited on synchronously, by parking the calling thread.
+//!
+//! ```rust
+//! # #[cfg(feature = "std")]
+//! # {
+//! use std::time::Duration;
+//!
+//! use compact_waitgroup::WaitGroup;
+//!
+//! let (mut wg, factory) = WaitGroup::new();
+//! let token = factory.into_token();
+//!
+//! assert!(!wg.wait_timeout(Duration::from_millis(10)));
+//!
+//! std::thread::spawn(move || {
+//!     // Long-running task
+//!     token.release();
+//! });
+//!
+//! wg.wait_blocking();
+//! assert!(wg.is_done());
+//! # }
+//! ```
+//!
 //! # Memory Layout
 //!
 //! This crate is designed to be extremely lightweight. The memory footprint
@@ -89,23 +142,84 @@
 //! [`MonoWaitGroup`]. It switches to a dedicated, stripped-down layout that
 //! removes the reference counter.
 //!
-//! | Component           | Default (64-bit) | With `compact-mono` | Saving      |
-//! | ------------------- | ---------------- | ------------------- | ----------- |
-//! | **[`WaitGroup`]**     | 32 bytes         | 32 bytes            | 0 bytes     |
-//! | **[`MonoWaitGroup`]** | **32 bytes**     | **24 bytes**        | **8 bytes** |
+//! Waiters polling through a shared reference, such as [`WaitGroup::wait`],
+//! and tokens awaiting the waiter being dropped, with
+//! [`GroupToken::abandoned`], keep their wakers in a list allocated on first
+//! use.
+//!
+//! | Component               | Default (64-bit) | With `compact-mono` | Saving      |
+//! | ----------------------- | ---------------- | ------------------- | ----------- |
+//! | **[`WaitGroup`]**       | 40 bytes         | 40 bytes            | 0 bytes     |
+//! | **[`MonoWaitGroup`]**   | **40 bytes**     | **32 bytes**        | **8 bytes** |
+//! | **[`SharedWaitGroup`]** | 48 bytes         | 48 bytes            | 0 bytes     |
+//!
+//! The `stats` feature records lifetime statistics of the [`GroupToken`]s,
+//! retrievable with `WaitGroup::stats`. It adds a lock and counters to the
+//! layout of [`WaitGroup`], and a timestamp to every [`GroupToken`].
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+#[cfg(feature = "std")]
+mod blocking;
+mod bounded;
+mod error_group;
 mod ext;
+mod gate;
 mod group;
+#[cfg(feature = "std")]
+mod heartbeat;
+#[cfg(feature = "futures-io")]
+mod io;
+mod labeled;
 mod layout;
+#[cfg(feature = "std")]
+mod panic;
+mod shutdown;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "futures-core")]
+mod stream;
 mod sync;
+mod tracker;
 mod twin_ref;
 mod utils;
+mod waker;
 
+#[cfg(feature = "std")]
+pub use crate::heartbeat::{
+    HeartbeatGroupToken, HeartbeatGroupTokenFactory, HeartbeatWaitGroup, StalledToken,
+};
+#[cfg(feature = "futures-io")]
+pub use crate::io::{GroupTokenAsyncReadExt, GroupTokenReleaseOnEof};
+#[cfg(feature = "std")]
+pub use crate::panic::{GroupPoisoned, PanicGroupToken, PanicGroupTokenFactory, PanicWaitGroup};
+#[cfg(feature = "stats")]
+pub use crate::stats::TokenStats;
+#[cfg(feature = "futures-core")]
+pub use crate::stream::{GroupTokenReleaseOnEnd, GroupTokenStreamExt};
 pub use crate::{
-    ext::{GroupTokenExt, GroupTokenFuncExt, GroupTokenReleaseOnDrop, GroupTokenReleaseOnReady},
-    group::{GroupToken, GroupTokenFactory, MonoGroupToken, MonoWaitGroup, WaitGroup},
+    bounded::{Acquire, BoundedGroupToken, BoundedGroupTokenFactory, BoundedWaitGroup},
+    error_group::{
+        AllErrors, ErrorGroupToken, ErrorGroupTokenFactory, ErrorMode, ErrorWaitGroup, FirstError,
+    },
+    ext::{
+        GroupTokenAbortOnAbandon, GroupTokenAsyncFuncExt, GroupTokenExt, GroupTokenFuncExt,
+        GroupTokenReleaseOnDrop, GroupTokenReleaseOnReady, GroupTokenReleaseOnStart,
+        GroupTokenType,
+    },
+    gate::Gate,
+    group::{
+        Abandoned, CounterWaitGroup, GroupClosed, GroupToken, GroupTokenFactory, MonoGroupToken,
+        MonoValueGroupToken, MonoValueWaitGroup, MonoWaitGroup, ReusableWaitGroup,
+        SharedGroupToken, SharedGroupTokenFactory, SharedWaitGroup, StartupGroupTokenFactory,
+        StartupTracked, TimedOut, Wait, WaitAbandoned, WaitGroup, WaitGroupSnapshot, WaitShared,
+        WithTimeout,
+    },
+    labeled::{LabeledGroupToken, LabeledGroupTokenFactory, LabeledWaitGroup, WaitingOn},
+    shutdown::{Shutdown, ShutdownToken},
+    tracker::{TaskTracker, TaskTrackerToken, WaitTracker},
 };
 
 #[cfg(test)]
@@ -0,0 +1,185 @@
+use core::{
+    any::Any,
+    fmt,
+    panic::{RefUnwindSafe, UnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{boxed::Box, panic, thread};
+
+use crate::{ErrorGroupToken, ErrorGroupTokenFactory, ErrorWaitGroup};
+
+/// Error returned when a task of a [`PanicWaitGroup`] has panicked.
+///
+/// Carries the panic payload if the panic was caught, for example by
+/// [`GroupTokenFuncExt::release_on_return`](crate::GroupTokenFuncExt::release_on_return).
+pub struct GroupPoisoned {
+    payload: Option<Box<dyn Any + Send + 'static>>,
+}
+
+impl UnwindSafe for GroupPoisoned {}
+impl RefUnwindSafe for GroupPoisoned {}
+
+impl GroupPoisoned {
+    /// Returns a reference to the panic payload, if captured.
+    #[inline]
+    pub fn payload(&self) -> Option<&(dyn Any + Send + 'static)> {
+        self.payload.as_deref()
+    }
+
+    /// Extracts the panic payload, if captured.
+    #[inline]
+    pub fn into_payload(self) -> Option<Box<dyn Any + Send + 'static>> {
+        self.payload
+    }
+
+    /// Resumes the panic with the captured payload.
+    ///
+    /// Panics with a new message if no payload was captured.
+    pub fn resume_unwind(self) -> ! {
+        match self.payload {
+            Some(payload) => panic::resume_unwind(payload),
+            None => panic!("{self}"),
+        }
+    }
+}
+
+impl fmt::Debug for GroupPoisoned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupPoisoned")
+            .field("payload", &self.payload.as_ref().map(|_| ..))
+            .finish()
+    }
+}
+
+impl fmt::Display for GroupPoisoned {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a task of the group has panicked")
+    }
+}
+
+impl std::error::Error for GroupPoisoned {}
+
+/// WaitGroup that is poisoned when a task panics.
+///
+/// A [`PanicGroupToken`] dropped while its thread is panicking poisons the
+/// group, and the waiter resolves to [`GroupPoisoned`] instead of `Ok(())`.
+///
+/// Requires the `std` feature.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+///
+/// The poisoning is handed out on the first completion. It is safe to poll
+/// again after completion, which resolves to `Ok(())`.
+///
+/// ```rust
+/// # use compact_waitgroup::{GroupTokenFuncExt, PanicWaitGroup};
+/// # futures_executor::block_on(async {
+/// let (wg, factory) = PanicWaitGroup::new();
+///
+/// let task = || panic!("boom");
+/// let handle = std::thread::spawn(task.release_on_return(factory.into_token()));
+/// assert_eq!(handle.join().unwrap(), None);
+///
+/// let poisoned = wg.await.unwrap_err();
+/// assert_eq!(poisoned.payload().unwrap().downcast_ref(), Some(&"boom"));
+/// # });
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct PanicWaitGroup(ErrorWaitGroup<GroupPoisoned>);
+
+/// Clonable group token of [`PanicWaitGroup`].
+///
+/// Dropping this token while the thread is panicking poisons the associated
+/// [`PanicWaitGroup`].
+#[must_use]
+#[derive(Clone, Debug)]
+pub struct PanicGroupToken(ErrorGroupToken<GroupPoisoned>);
+
+/// Factory of [`PanicGroupToken`].
+///
+/// Provides methods to obtain or scope the clonable token for distribution.
+#[must_use]
+#[derive(Debug)]
+pub struct PanicGroupTokenFactory(PanicGroupToken);
+
+impl PanicWaitGroup {
+    /// Creates a new `PanicWaitGroup` and a [`PanicGroupTokenFactory`].
+    pub fn new() -> (Self, PanicGroupTokenFactory) {
+        let (wg, factory): (_, ErrorGroupTokenFactory<_>) = ErrorWaitGroup::new();
+        (
+            Self(wg),
+            PanicGroupTokenFactory(PanicGroupToken(factory.into_token())),
+        )
+    }
+
+    /// Checks if the `PanicWaitGroup` has completed.
+    ///
+    /// This returns `true` if all [`PanicGroupToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.is_done()
+    }
+}
+
+impl Future for PanicWaitGroup {
+    type Output = Result<(), GroupPoisoned>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl PanicGroupTokenFactory {
+    /// Consumes the inner token.
+    ///
+    /// This is equivalent to dropping the factory.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Extracts the inner [`PanicGroupToken`].
+    #[inline]
+    pub fn into_token(self) -> PanicGroupToken {
+        self.0
+    }
+
+    /// Executes a closure with the inner [`PanicGroupToken`].
+    #[inline]
+    pub fn scope<T, F: FnOnce(PanicGroupToken) -> T>(self, func: F) -> T {
+        func(self.into_token())
+    }
+}
+
+impl PanicGroupToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Poisons the group with a panic payload and consumes the token.
+    #[inline]
+    pub fn poison(self, payload: Box<dyn Any + Send + 'static>) {
+        self.0.report(GroupPoisoned {
+            payload: Some(payload),
+        });
+    }
+}
+
+impl Drop for PanicGroupToken {
+    #[inline]
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.report(GroupPoisoned { payload: None });
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use core::{
+    fmt,
+    task::{Context, Poll},
+};
+
+use crate::{
+    gate::Gate,
+    group::{
+        GroupClosed, GroupToken, SharedGroupToken, SharedWaitGroup, Wait, WaitShared,
+        private::Waitable,
+    },
+    sync::SpinLock,
+};
+
+/// Graceful shutdown controller.
+///
+/// Broadcasts a stop signal to every [`ShutdownToken`] with
+/// [`trigger`](Self::trigger), then waits for all of them to be released.
+/// The stop signal is observed by [`ShutdownToken::stopping`].
+///
+/// Dropping the controller also triggers the stop signal.
+///
+/// # Cancellation safety
+///
+/// The future returned by [`wait`](Self::wait) is cancellation safe.
+///
+/// ```rust
+/// # use compact_waitgroup::Shutdown;
+/// # futures_executor::block_on(async {
+/// let shutdown = Shutdown::new();
+///
+/// let token = shutdown.token().unwrap();
+/// let worker = async move {
+///     // Serve until the stop signal is received
+///     token.stopping().await;
+///     token.release();
+/// };
+///
+/// shutdown.trigger();
+/// assert!(shutdown.token().is_err());
+///
+/// worker.await;
+/// shutdown.wait().await;
+/// assert!(shutdown.is_done());
+/// # });
+/// ```
+#[must_use]
+pub struct Shutdown {
+    gate: Gate,
+    stopping: SharedWaitGroup,
+    trigger: SpinLock<Option<SharedGroupToken>>,
+}
+
+/// Token of a [`Shutdown`].
+///
+/// Keeps the [`Shutdown`] from draining until released, and observes its stop
+/// signal.
+#[must_use]
+#[derive(Clone, Debug)]
+pub struct ShutdownToken {
+    _token: GroupToken,
+    stopping: SharedWaitGroup,
+}
+
+impl Shutdown {
+    /// Creates a new `Shutdown`.
+    pub fn new() -> Self {
+        let (stopping, factory) = SharedWaitGroup::new();
+        Self {
+            gate: Gate::new(),
+            stopping,
+            trigger: SpinLock::new(Some(factory.into_token())),
+        }
+    }
+
+    /// Creates a new [`ShutdownToken`].
+    ///
+    /// Returns [`GroupClosed`] if the shutdown has been triggered.
+    pub fn token(&self) -> Result<ShutdownToken, GroupClosed> {
+        Ok(ShutdownToken {
+            _token: self.gate.enter()?,
+            stopping: self.stopping.clone(),
+        })
+    }
+
+    /// Broadcasts the stop signal, and rejects new tokens from now on.
+    ///
+    /// Triggering an already triggered shutdown does nothing.
+    pub fn trigger(&self) {
+        // Close the gate first, so that no token can be created once the stop
+        // signal is observed.
+        self.gate.close();
+        drop(self.trigger.with(Option::take));
+    }
+
+    /// Checks if the `Shutdown` has been triggered.
+    #[inline]
+    pub fn is_triggered(&self) -> bool {
+        self.stopping.is_done()
+    }
+
+    /// Checks if the `Shutdown` has completed.
+    ///
+    /// This returns `true` if the shutdown is triggered and all
+    /// [`ShutdownToken`]s have been dropped.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.gate.is_done()
+    }
+
+    /// Returns the number of [`ShutdownToken`]s still alive.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.gate.outstanding()
+    }
+
+    /// Polls the `Shutdown` for completion through a shared reference.
+    ///
+    /// Registers the waker as [`Gate::poll_done`] does.
+    #[inline]
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.gate.poll_done(cx)
+    }
+
+    /// Returns a future that waits for the `Shutdown` to be triggered and
+    /// drained.
+    #[inline]
+    pub fn wait(&self) -> Wait<'_, Self> {
+        Wait::new(self)
+    }
+}
+
+impl ShutdownToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+
+    /// Checks if the [`Shutdown`] has been triggered.
+    #[inline]
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.is_done()
+    }
+
+    /// Polls for the stop signal, registering the waker of `cx`.
+    #[inline]
+    pub fn poll_stopping(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.stopping.poll_done(cx)
+    }
+
+    /// Returns a future that waits for the stop signal.
+    ///
+    /// The future is cancellation safe, and unregisters its waker once
+    /// dropped.
+    #[inline]
+    pub fn stopping(&self) -> WaitShared<'_> {
+        self.stopping.wait()
+    }
+}
+
+impl Waitable for Shutdown {
+    #[inline]
+    fn poll_wait(&self, cx: &mut Context<'_>, key: &mut Option<usize>) -> Poll<()> {
+        self.gate.poll_wait(cx, key)
+    }
+
+    #[inline]
+    fn remove_waker(&self, key: usize) {
+        self.gate.remove_waker(key);
+    }
+}
+
+impl Default for Shutdown {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("triggered", &self.is_triggered())
+            .field("done", &self.is_done())
+            .field("outstanding", &self.outstanding())
+            .finish()
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::{Duration, Instant};
+
+use crate::{layout::SharedLayout, utils::*};
+
+const BUCKETS: usize = TokenStats::LIFETIME_BOUNDS.len() + 1;
+
+/// Statistics recorded by the [`GroupToken`](crate::GroupToken)s of a group.
+///
+/// Lock-free, so recording never contends with the other tokens.
+#[derive(Debug)]
+pub(crate) struct StatsRecorder {
+    created: AtomicUsize,
+    released: AtomicUsize,
+    alive: AtomicUsize,
+    peak_outstanding: AtomicUsize,
+    /// In nanoseconds, saturated to `u64::MAX`.
+    max_lifetime: AtomicU64,
+    lifetimes: [AtomicUsize; BUCKETS],
+}
+
+impl Default for StatsRecorder {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            created: AtomicUsize::new(0),
+            released: AtomicUsize::new(0),
+            alive: AtomicUsize::new(0),
+            peak_outstanding: AtomicUsize::new(0),
+            max_lifetime: AtomicU64::new(0),
+            lifetimes: core::array::from_fn(|_| AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl StatsRecorder {
+    #[inline]
+    fn create(&self) {
+        self.created.fetch_add(1, atomic::Relaxed);
+        let alive = self.alive.fetch_add(1, atomic::Relaxed) + 1;
+        self.peak_outstanding.fetch_max(alive, atomic::Relaxed);
+    }
+
+    #[inline]
+    fn release(&self, lifetime: Duration) {
+        let nanos = u64::try_from(lifetime.as_nanos()).unwrap_or(u64::MAX);
+        self.max_lifetime.fetch_max(nanos, atomic::Relaxed);
+        let bucket = TokenStats::LIFETIME_BOUNDS
+            .iter()
+            .position(|&bound| lifetime < bound)
+            .unwrap_or(BUCKETS - 1);
+        self.lifetimes[bucket].fetch_add(1, atomic::Relaxed);
+        self.alive.fetch_sub(1, atomic::Relaxed);
+        // Pairs with the acquire load in `snapshot`, so that a snapshot never
+        // sees a release without its creation.
+        self.released.fetch_add(1, atomic::Release);
+    }
+
+    /// Clears the statistics, for a new round of the group.
+    ///
+    /// Must not race with the tokens of the group.
+    #[inline]
+    pub fn reset(&self) {
+        self.created.store(0, atomic::Relaxed);
+        self.released.store(0, atomic::Relaxed);
+        self.alive.store(0, atomic::Relaxed);
+        self.peak_outstanding.store(0, atomic::Relaxed);
+        self.max_lifetime.store(0, atomic::Relaxed);
+        for bucket in &self.lifetimes {
+            bucket.store(0, atomic::Relaxed);
+        }
+    }
+}
+
+/// Creation time of a [`GroupToken`](crate::GroupToken), recorded in the
+/// statistics of its group.
+#[derive(Debug)]
+pub(crate) struct TokenLifetime(Instant);
+
+impl TokenLifetime {
+    #[inline]
+    pub fn start(stats: &StatsRecorder) -> Self {
+        stats.create();
+        Self(Instant::now())
+    }
+
+    /// Must be called once, before the token is released.
+    #[inline]
+    pub fn end(&self, stats: &StatsRecorder) {
+        stats.release(self.0.elapsed());
+    }
+}
+
+/// Lifetime statistics of the [`GroupToken`](crate::GroupToken)s of a
+/// [`WaitGroup`](crate::WaitGroup).
+///
+/// Created by [`WaitGroup::stats`](crate::WaitGroup::stats). The counted
+/// tokens are the [`GroupToken`](crate::GroupToken)s of the group, which
+/// include:
+///
+/// - the token held by the [`GroupTokenFactory`](crate::GroupTokenFactory);
+/// - the token held by a child group created with
+///   [`child`](crate::GroupToken::child), released once the child group
+///   completes;
+/// - a token converted with [`into_waker`](crate::GroupToken::into_waker),
+///   released by the first wake of the waker or by dropping all its clones.
+///
+/// The statistics of a [`ReusableWaitGroup`](crate::ReusableWaitGroup) only
+/// cover the current round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenStats {
+    /// Number of tokens created.
+    pub created: usize,
+    /// Number of tokens released.
+    pub released: usize,
+    /// Highest number of tokens alive at the same time.
+    pub peak_outstanding: usize,
+    /// Longest lifetime of the released tokens.
+    pub max_lifetime: Duration,
+    /// Histogram of the lifetimes of the released tokens.
+    ///
+    /// The bucket `i` counts the lifetimes below
+    /// [`LIFETIME_BOUNDS[i]`](Self::LIFETIME_BOUNDS), and not counted by the
+    /// previous buckets. The last bucket counts the remaining lifetimes.
+    pub lifetimes: [usize; 6],
+}
+
+impl TokenStats {
+    /// Upper bounds of the buckets of [`lifetimes`](Self::lifetimes).
+    pub const LIFETIME_BOUNDS: [Duration; 5] = [
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+        Duration::from_millis(100),
+        Duration::from_secs(1),
+        Duration::from_secs(10),
+    ];
+
+    /// Returns the number of tokens still alive.
+    #[inline]
+    pub fn outstanding(&self) -> usize {
+        self.created - self.released
+    }
+}
+
+#[inline]
+pub(crate) fn snapshot(layout: &SharedLayout) -> TokenStats {
+    let stats = layout.stats();
+    let released = stats.released.load(atomic::Acquire);
+    TokenStats {
+        created: stats.created.load(atomic::Relaxed),
+        released,
+        peak_outstanding: stats.peak_outstanding.load(atomic::Relaxed),
+        max_lifetime: Duration::from_nanos(stats.max_lifetime.load(atomic::Relaxed)),
+        lifetimes: core::array::from_fn(|i| stats.lifetimes[i].load(atomic::Relaxed)),
+    }
+}
@@ -0,0 +1,69 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use derive_more::Into;
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::ext::GroupTokenType;
+
+/// Extension trait for streams to automatically release group tokens.
+pub trait GroupTokenStreamExt<T>: Sized {
+    /// Releases the group token when the stream ends or is dropped.
+    #[inline]
+    fn release_on_end(self, token: T) -> GroupTokenReleaseOnEnd<Self, T> {
+        GroupTokenReleaseOnEnd {
+            inner: self,
+            token: Some(token),
+        }
+    }
+}
+
+impl<T: GroupTokenType, S: Stream> GroupTokenStreamExt<T> for S {}
+
+pin_project! {
+    /// Wrapper that releases a token when the stream ends or is dropped.
+    ///
+    /// Created by [`GroupTokenStreamExt::release_on_end`].
+    #[derive(Debug, Into)]
+    pub struct GroupTokenReleaseOnEnd<S, T> {
+        #[pin]
+        inner: S,
+        token: Option<T>,
+    }
+}
+
+impl<S, T> GroupTokenReleaseOnEnd<S, T> {
+    /// Returns a pinned mutable reference to the inner stream.
+    #[inline]
+    pub fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut S> {
+        self.project().inner
+    }
+
+    /// Returns a reference to the associated token if not yet released.
+    #[inline]
+    pub fn group_token(&self) -> Option<&T> {
+        self.token.as_ref()
+    }
+}
+
+impl<S: Stream, T> Stream for GroupTokenReleaseOnEnd<S, T> {
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let res = ready!(this.inner.poll_next(cx));
+        if res.is_none() {
+            drop(this.token.take());
+        }
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
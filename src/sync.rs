@@ -1,20 +1,39 @@
 use core::{
+    panic::{RefUnwindSafe, UnwindSafe},
     pin::Pin,
     task::{Context, Poll, Waker},
 };
 
+use alloc::{boxed::Box, vec::Vec};
 use derive_more::{Constructor, Deref};
 
 use crate::utils::*;
 
-pub(crate) type WaitGroupData = Option<Waker>;
+/// Wakers of a [`WaitGroupLayout`].
+///
+/// The waiter polled by value owns `waker`. The waiters polling through a
+/// shared reference, and the tokens waiting for the waiter to be dropped,
+/// register in `shared`, allocated on first use.
+#[derive(Debug, Default)]
+pub(crate) struct WaitGroupData {
+    waker: Option<Waker>,
+    shared: Option<Box<SharedWakers>>,
+}
+
+#[derive(Debug, Default)]
+struct SharedWakers {
+    waiters: WakerSlab,
+    abandon: WakerSlab,
+}
 
 /// # Safety
 ///
 /// - `state` must be a field exclusively reserved for `WaitGroupType`, and
-///   initialized to `0`.
+///   initialized to `0`, or to [`NESTED`] for the `SharedLayout` of a
+///   `NestedLayout`. [`NESTED`] is never changed afterwards, even by
+///   [`WaitGroupLayoutExt::reset`].
 /// - `slot` must be a field exclusively reserved for `WaitGroupType`, and the
-///   inner value should be initialized to `None`.
+///   inner value should be initialized to empty.
 pub(crate) unsafe trait WaitGroupLayout: Sized {
     fn state(&self) -> &AtomicU8;
     unsafe fn slot(&self) -> &UnsafeCell<WaitGroupData>;
@@ -26,91 +45,527 @@ pub(crate) trait WaitGroupLayoutExt: WaitGroupLayout {
         self.state().load(atomic::Acquire) & DONE != 0
     }
 
+    /// Checks if a waker is registered and not yet consumed.
+    #[inline]
+    fn has_waker(&self) -> bool {
+        self.state().load(atomic::Acquire) & (DONE | WAKER) == WAKER
+    }
+
+    /// Checks if the layout is allocated as a part of a larger one.
+    #[inline]
+    fn is_nested(&self) -> bool {
+        self.state().load(atomic::Relaxed) & NESTED != 0
+    }
+
+    /// Checks if the waiter side has been dropped.
+    #[inline]
+    fn is_abandoned(&self) -> bool {
+        self.state().load(atomic::Acquire) & ABANDONED != 0
+    }
+
+    /// Registers the waker of `cx` to be woken once the waiter side is
+    /// dropped, under `key`, or without a key if `key` is `None`.
+    fn poll_abandoned(&self, cx: &mut Context<'_>, key: Option<&mut Option<usize>>) -> Poll<()> {
+        if self.is_abandoned() {
+            return Poll::Ready(());
+        }
+        let waker = cx.waker();
+        let registered = unsafe {
+            self.with_data(Some(waker), |data| match key {
+                Some(key) => data.abandon().register(key, waker),
+                None => data.abandon().register_unkeyed(waker),
+            })
+        };
+        match registered {
+            Some(()) => Poll::Pending,
+            None => Poll::Ready(()),
+        }
+    }
+
+    /// Removes the waker registered under `key` by
+    /// [`WaitGroupLayoutExt::poll_abandoned`].
+    #[inline]
+    fn remove_abandon_waker(&self, key: usize) {
+        let waker = unsafe { self.with_data(None, |data| data.abandon().remove(key)) };
+        drop(waker);
+    }
+
+    /// Resets the state, except [`NESTED`], and drops the wakers.
+    ///
+    /// # Safety
+    ///
+    /// Requires exclusive access to the layout.
+    #[inline]
+    unsafe fn reset(&self) {
+        drop(unsafe { with_slot_mut(self, core::mem::take) });
+        self.state().fetch_and(NESTED, atomic::Relaxed);
+    }
+
     #[inline]
     unsafe fn send_done(&self) {
         let prev_state = self.state().fetch_or(DONE | LOCK, atomic::AcqRel);
-        if prev_state & LOCK == 0
-            && let Some(waker) = unsafe { with_slot_mut(self, |slot| slot.take()) }
+        if prev_state & LOCK == 0 {
+            let data = unsafe { with_slot_mut(self, core::mem::take) };
+            data.wake(DONE, None);
+        }
+    }
+
+    /// Sends abandoned, once the waiter side is dropped.
+    #[inline]
+    unsafe fn send_abandoned(&self) {
+        let prev_state = self.state().fetch_or(ABANDONED | LOCK, atomic::AcqRel);
+        if prev_state & LOCK == 0 {
+            let data = unsafe { with_slot_mut(self, core::mem::take) };
+            data.wake(ABANDONED, None);
+        }
+    }
+
+    /// Runs `f` with the wakers locked, unless done or abandoned.
+    ///
+    /// Returns `None` if done or abandoned, after waking the wakers of the
+    /// event other than `skip` if it is sent while locked. `WAKER` is updated
+    /// on unlock.
+    ///
+    /// Only the waiter side waits for done, and only the token side waits for
+    /// abandoned, so at most one of them can be sent while locked.
+    unsafe fn with_data<R, F: FnOnce(&mut WaitGroupData) -> R>(
+        &self,
+        skip: Option<&Waker>,
+        f: F,
+    ) -> Option<R> {
+        loop {
+            let prev_state = self.state().fetch_or(LOCK, atomic::Acquire);
+            if prev_state & (DONE | ABANDONED) != 0 {
+                return None;
+            }
+            if prev_state & LOCK == 0 {
+                break;
+            }
+            spin_loop();
+        }
+
+        let guard = UnlockGuard(self.state());
+        let (output, registered) = unsafe {
+            with_slot_mut(self, |data| {
+                let output = f(data);
+                (output, data.is_registered())
+            })
+        };
+        guard.defuse();
+
+        let prev_state = if registered {
+            self.state().fetch_or(WAKER, atomic::Relaxed);
+            self.state().fetch_and(!LOCK, atomic::AcqRel)
+        } else {
+            self.state().fetch_and(!(LOCK | WAKER), atomic::AcqRel)
+        };
+        if prev_state & (DONE | ABANDONED) != 0 {
+            let data = unsafe { with_slot_mut(self, core::mem::take) };
+            self.state().fetch_or(LOCK, atomic::Release);
+            data.wake(prev_state, skip);
+            return None;
+        }
+
+        Some(output)
+    }
+}
+
+impl<T: WaitGroupLayout> WaitGroupLayoutExt for T {}
+
+impl WaitGroupData {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            waker: None,
+            shared: None,
+        }
+    }
+
+    #[inline]
+    fn waiters(&mut self) -> &mut WakerSlab {
+        &mut self.shared.get_or_insert_default().waiters
+    }
+
+    #[inline]
+    fn abandon(&mut self) -> &mut WakerSlab {
+        &mut self.shared.get_or_insert_default().abandon
+    }
+
+    /// Checks if a waker of the waiter side is registered.
+    #[inline]
+    fn is_registered(&self) -> bool {
+        self.waker.is_some()
+            || self
+                .shared
+                .as_ref()
+                .is_some_and(|shared| !shared.waiters.is_empty())
+    }
+
+    /// Wakes the wakers waiting for the events set in `state`, skipping
+    /// `skip`, and drops the others.
+    fn wake(self, state: u8, skip: Option<&Waker>) {
+        if state & DONE != 0
+            && let Some(waker) = self.waker
+            && !skip.is_some_and(|skip| skip.will_wake(&waker))
         {
             waker.wake();
         }
+        if let Some(shared) = self.shared {
+            if state & DONE != 0 {
+                shared.waiters.wake_all(skip);
+            }
+            if state & ABANDONED != 0 {
+                shared.abandon.wake_all(skip);
+            }
+        }
     }
 }
 
-impl<T: WaitGroupLayout> WaitGroupLayoutExt for T {}
+/// # Safety
+///
+/// - `state` must be a field exclusively reserved for `MultiWaitGroupType`, and
+///   initialized to `0`.
+/// - `wakers` must be a field exclusively reserved for `MultiWaitGroupType`,
+///   and the inner value should be initialized to empty.
+pub(crate) unsafe trait MultiWaitGroupLayout: Sized {
+    fn state(&self) -> &AtomicU8;
+    unsafe fn wakers(&self) -> &UnsafeCell<WakerSlab>;
+}
+
+pub(crate) trait MultiWaitGroupLayoutExt: MultiWaitGroupLayout {
+    #[inline]
+    fn is_done(&self) -> bool {
+        self.state().load(atomic::Acquire) & DONE != 0
+    }
+
+    #[inline]
+    unsafe fn send_done(&self) {
+        let prev_state = self.state().fetch_or(DONE | LOCK, atomic::AcqRel);
+        if prev_state & LOCK == 0 {
+            let wakers = unsafe { with_cell_mut(self.wakers(), core::mem::take) };
+            wakers.wake_all(None);
+        }
+    }
+
+    /// Registers the waker of `cx` under `key`, or without a key if `key` is
+    /// `None`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the waiter side, before
+    /// [`MultiWaitGroupLayoutExt::drop_wakers`].
+    unsafe fn poll_shared(
+        &self,
+        cx: &mut Context<'_>,
+        key: Option<&mut Option<usize>>,
+    ) -> Poll<()> {
+        let waker = cx.waker();
+        let registered = unsafe {
+            self.with_wakers(Some(waker), |wakers| match key {
+                Some(key) => wakers.register(key, waker),
+                None => wakers.register_unkeyed(waker),
+            })
+        };
+        match registered {
+            Some(()) => Poll::Pending,
+            None => Poll::Ready(()),
+        }
+    }
+
+    /// Removes the waker registered under `key`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the waiter side, before
+    /// [`MultiWaitGroupLayoutExt::drop_wakers`].
+    unsafe fn remove_waker(&self, key: usize) {
+        let waker = unsafe { self.with_wakers(None, |wakers| wakers.remove(key)) };
+        drop(waker);
+    }
+
+    /// Runs `f` with the wakers locked, unless done.
+    ///
+    /// Returns `None` if done, after waking the wakers other than `skip`
+    /// if done is sent while locked.
+    unsafe fn with_wakers<R, F: FnOnce(&mut WakerSlab) -> R>(
+        &self,
+        skip: Option<&Waker>,
+        f: F,
+    ) -> Option<R> {
+        loop {
+            let prev_state = self.state().fetch_or(LOCK, atomic::Acquire);
+            if prev_state & DONE != 0 {
+                return None;
+            }
+            if prev_state & LOCK == 0 {
+                break;
+            }
+            spin_loop();
+        }
+
+        let guard = UnlockGuard(self.state());
+        let output = unsafe { with_cell_mut(self.wakers(), f) };
+        guard.defuse();
+
+        let prev_state = self.state().fetch_and(!LOCK, atomic::AcqRel);
+        if prev_state & DONE != 0 {
+            let wakers = unsafe { with_cell_mut(self.wakers(), core::mem::take) };
+            self.state().fetch_or(LOCK, atomic::Release);
+            wakers.wake_all(skip);
+            return None;
+        }
+
+        Some(output)
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called once, when the waiter side is dropped.
+    #[inline]
+    unsafe fn drop_wakers(&self) {
+        let prev_state = self.state().fetch_or(LOCK, atomic::Acquire);
+        if prev_state & LOCK == 0 {
+            drop(unsafe { with_cell_mut(self.wakers(), core::mem::take) });
+        }
+    }
+}
+
+impl<T: MultiWaitGroupLayout> MultiWaitGroupLayoutExt for T {}
+
+/// Waker registered in a [`WakerSlab`].
+#[derive(Debug)]
+struct WakerSlot {
+    waker: Waker,
+    keyed: bool,
+}
+
+/// List of wakers, where each waiter holding a key replaces or removes its
+/// own waker.
+///
+/// Wakers registered without a key are deduplicated instead, and kept until
+/// the list is drained.
+#[derive(Debug, Default)]
+pub(crate) struct WakerSlab(Vec<Option<WakerSlot>>);
+
+impl WakerSlab {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Registers `waker` under `key`, which is assigned if `None`.
+    pub fn register(&mut self, key: &mut Option<usize>, waker: &Waker) {
+        let slot = key
+            .and_then(|key| self.0.get_mut(key))
+            .and_then(Option::as_mut);
+        match slot {
+            Some(slot) if slot.keyed => {
+                if !slot.waker.will_wake(waker) {
+                    slot.waker.clone_from(waker);
+                }
+            }
+            _ => {
+                *key = Some(self.insert(WakerSlot {
+                    waker: waker.clone(),
+                    keyed: true,
+                }));
+            }
+        }
+    }
+
+    /// Registers `waker` without a key, unless an equivalent one has been
+    /// registered the same way.
+    pub fn register_unkeyed(&mut self, waker: &Waker) {
+        let registered = self
+            .0
+            .iter()
+            .flatten()
+            .any(|slot| !slot.keyed && slot.waker.will_wake(waker));
+        if !registered {
+            self.insert(WakerSlot {
+                waker: waker.clone(),
+                keyed: false,
+            });
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Removes the waker registered under `key`.
+    pub fn remove(&mut self, key: usize) -> Option<Waker> {
+        let slot = self.0.get_mut(key)?.take();
+        while let Some(None) = self.0.last() {
+            self.0.pop();
+        }
+        slot.map(|slot| slot.waker)
+    }
+
+    /// Wakes the registered wakers, skipping equivalent ones and `skip`.
+    pub fn wake_all(self, skip: Option<&Waker>) {
+        let wakers = self.0.iter().flatten().map(|slot| &slot.waker);
+        for (i, waker) in wakers.clone().enumerate() {
+            let woken = wakers.clone().take(i).any(|old| old.will_wake(waker));
+            if !woken && !skip.is_some_and(|skip| skip.will_wake(waker)) {
+                waker.wake_by_ref();
+            }
+        }
+    }
+
+    #[inline]
+    fn insert(&mut self, slot: WakerSlot) -> usize {
+        match self.0.iter().position(Option::is_none) {
+            Some(key) => {
+                self.0[key] = Some(slot);
+                key
+            }
+            None => {
+                self.0.push(Some(slot));
+                self.0.len() - 1
+            }
+        }
+    }
+}
 
 #[must_use]
 #[derive(Debug, Constructor, Deref)]
 pub(crate) struct WaitGroupWrapper<T: WaitGroupLayout>(T);
 
-const DONE: u8 = 0b01;
-const LOCK: u8 = 0b10;
+const DONE: u8 = 0b001;
+const LOCK: u8 = 0b010;
+/// Set while a waker of the waiter side is registered.
+const WAKER: u8 = 0b100;
+/// Set once the waiter side is dropped.
+const ABANDONED: u8 = 0b1000;
+/// Set on creation of the `SharedLayout` of a `NestedLayout`, and never
+/// cleared.
+pub(crate) const NESTED: u8 = 0b10000;
 
 #[inline]
-unsafe fn with_slot_mut<T: WaitGroupLayout, R, F: FnOnce(&mut WaitGroupData) -> R>(
-    val: &T,
-    f: F,
-) -> R {
+pub(crate) unsafe fn with_cell<D, R, F: FnOnce(&D) -> R>(cell: &UnsafeCell<D>, f: F) -> R {
     #[cfg(not(loom))]
     {
-        f(unsafe { &mut *val.slot().get() })
+        f(unsafe { &*cell.get() })
     }
     #[cfg(loom)]
     {
-        unsafe { val.slot() }
-            .get()
-            .with(|ptr| f(unsafe { &mut *ptr.cast_mut() }))
+        cell.with(|ptr| f(unsafe { &*ptr }))
     }
 }
 
-impl<T: WaitGroupLayout> Future for WaitGroupWrapper<T> {
-    type Output = ();
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let prev_state = self.state().fetch_or(LOCK, atomic::Acquire);
+#[inline]
+pub(crate) unsafe fn with_cell_mut<D, R, F: FnOnce(&mut D) -> R>(cell: &UnsafeCell<D>, f: F) -> R {
+    #[cfg(not(loom))]
+    {
+        f(unsafe { &mut *cell.get() })
+    }
+    #[cfg(loom)]
+    {
+        cell.with_mut(|ptr| f(unsafe { &mut *ptr }))
+    }
+}
 
-        if prev_state & DONE != 0 {
-            return Poll::Ready(());
-        }
+#[inline]
+unsafe fn with_slot_mut<T: WaitGroupLayout, R, F: FnOnce(&mut WaitGroupData) -> R>(
+    val: &T,
+    f: F,
+) -> R {
+    unsafe { with_cell_mut(val.slot(), f) }
+}
 
-        debug_assert!(prev_state & LOCK == 0);
+impl<T: WaitGroupLayout> WaitGroupWrapper<T> {
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
 
-        let guard = UnlockGuard(self.state());
+    /// Polls for completion, registering the waker of `cx` as the one of the
+    /// waiter itself.
+    pub fn poll_done(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let waker = cx.waker();
+        let registered = unsafe {
+            self.0.with_data(Some(waker), |data| match &mut data.waker {
+                Some(old) => old.clone_from(waker),
+                slot => *slot = Some(waker.clone()),
+            })
+        };
+        match registered {
+            Some(()) => Poll::Pending,
+            None => Poll::Ready(()),
+        }
+    }
 
+    /// Polls for completion through a shared reference, registering the
+    /// waker of `cx` under `key`, or without a key if `key` is `None`.
+    ///
+    /// Every waker registered this way is woken on completion.
+    pub fn poll_shared(&self, cx: &mut Context<'_>, key: Option<&mut Option<usize>>) -> Poll<()> {
         let waker = cx.waker();
-        unsafe {
-            with_slot_mut(&self.0, |slot| {
-                match slot {
-                    Some(old) if old.will_wake(waker) => {}
-                    _ => {
-                        *slot = Some(waker.clone());
-                    }
-                };
-            });
+        let registered = unsafe {
+            self.0.with_data(Some(waker), |data| match key {
+                Some(key) => data.waiters().register(key, waker),
+                None => data.waiters().register_unkeyed(waker),
+            })
+        };
+        match registered {
+            Some(()) => Poll::Pending,
+            None => Poll::Ready(()),
         }
+    }
 
-        guard.defuse();
+    /// Removes the waker registered under `key` by
+    /// [`WaitGroupWrapper::poll_shared`].
+    pub fn remove_waker(&self, key: usize) {
+        let waker = unsafe { self.0.with_data(None, |data| data.waiters().remove(key)) };
+        drop(waker);
+    }
+}
 
-        let prev_state = self.state().fetch_and(!LOCK, atomic::AcqRel);
-        if prev_state & DONE != 0 {
-            drop(unsafe { with_slot_mut(&self.0, |slot| slot.take()) });
-            self.state().fetch_or(LOCK, atomic::Release);
-            return Poll::Ready(());
-        }
+impl<T: WaitGroupLayout + Unpin> Future for WaitGroupWrapper<T> {
+    type Output = ();
 
-        Poll::Pending
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().poll_done(cx)
     }
 }
 
 impl<T: WaitGroupLayout> Drop for WaitGroupWrapper<T> {
     #[inline]
     fn drop(&mut self) {
-        let prev_state = self.state().fetch_or(LOCK, atomic::Acquire);
-        if prev_state & LOCK == 0
-            && let Some(waker) = unsafe { with_slot_mut(&self.0, |slot| slot.take()) }
-        {
-            drop(waker);
+        unsafe { self.send_abandoned() };
+    }
+}
+
+/// Minimal spin lock for data rarely accessed concurrently.
+#[derive(Debug)]
+pub(crate) struct SpinLock<T> {
+    locked: AtomicU8,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for SpinLock<T> {}
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+impl<T: UnwindSafe> RefUnwindSafe for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    #[inline]
+    pub fn new(data: T) -> Self {
+        Self {
+            locked: AtomicU8::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    #[inline]
+    pub fn with<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        while self.locked.swap(LOCK, atomic::Acquire) != 0 {
+            spin_loop();
         }
+        let _guard = UnlockGuard(&self.locked);
+        unsafe { with_cell_mut(&self.data, f) }
     }
 }
 
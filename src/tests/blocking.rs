@@ -0,0 +1,64 @@
+#![cfg(all(feature = "std", not(loom)))]
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{GroupTokenFuncExt, MonoWaitGroup, WaitGroup};
+
+#[test]
+fn test_wg_wait_blocking() {
+    let (mut wg, factory) = WaitGroup::new();
+    let handles = core::iter::repeat_n(factory.into_token(), 4)
+        .map(|token| {
+            thread::spawn((|| thread::sleep(Duration::from_millis(10))).release_on_return(token))
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    wg.wait_blocking();
+    assert!(wg.is_done());
+    wg.wait_blocking();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_mono_wg_wait_blocking() {
+    let (mut wg, token) = MonoWaitGroup::new();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        token.release();
+    });
+    wg.wait_blocking();
+    assert!(wg.is_done());
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_wg_wait_timeout() {
+    let (mut wg, token) = WaitGroup::new();
+    assert!(!wg.wait_timeout(Duration::from_millis(10)));
+    assert!(!wg.wait_deadline(Instant::now()));
+    let handle = thread::spawn(move || token.release());
+    assert!(wg.wait_timeout(Duration::from_secs(60)));
+    assert!(wg.wait_deadline(Instant::now()));
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_mono_wg_wait_timeout() {
+    let (mut wg, token) = MonoWaitGroup::new();
+    assert!(!wg.wait_timeout(Duration::from_millis(10)));
+    let handle = thread::spawn(move || token.release());
+    assert!(wg.wait_timeout(Duration::MAX));
+    handle.join().unwrap();
+}
+
+#[futures_test::test]
+async fn test_wg_await_after_timeout() {
+    let (mut wg, token) = WaitGroup::new();
+    assert!(!wg.wait_timeout(Duration::from_millis(1)));
+    token.release();
+    wg.await;
+}
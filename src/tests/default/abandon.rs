@@ -0,0 +1,192 @@
+use core::{
+    future::{pending, ready},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use alloc::sync::Arc as StdArc;
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    Abandoned, GroupTokenExt, MonoWaitGroup, ReusableWaitGroup, WaitGroup,
+    tests::utils::{Arc, FutureTestExt, NoopWake, SharedData},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_abandoned() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let token = token.into_token();
+    let token_cloned = token.clone();
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+    assert_eq!(token_cloned.poll_abandoned(&mut cx), Poll::Pending);
+    drop(wg);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Ready(()));
+    assert_eq!(token_cloned.poll_abandoned(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_abandoned_after_done() {
+    let wake = StdArc::new(NoopWake);
+    let waker = Waker::from(wake.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (mut wg, token) = WaitGroup::new();
+    let token = token.into_token();
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(StdArc::strong_count(&wake), 4);
+    token.release();
+    // Nobody can wait for the group to be abandoned once it has completed.
+    assert_eq!(StdArc::strong_count(&wake), 2);
+    assert_eq!(core::pin::Pin::new(&mut wg).poll(&mut cx), Poll::Ready(()));
+    drop(wg);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_abandoned() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+    drop(wg);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_wg_abandoned_in_another_thread() {
+    let canary = Arc::new(SharedData::new());
+    let inspector = canary.clone();
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = WaitGroup::new();
+    let token = token.into_token();
+    async move {
+        token.abandoned().await;
+        canary.store();
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    drop(wg);
+    bg_wg.await;
+    assert!(inspector.load());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_mono_wg_abandoned_in_another_thread() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = MonoWaitGroup::new();
+    async move {
+        token.abandoned().await;
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    drop(wg);
+    bg_wg.await;
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_abort_on_abandon_ready() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let mut fut = core::pin::pin!(ready(42).abort_on_abandon(token.into_token()));
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(42)));
+    assert!(fut.group_token().is_none());
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_abort_on_abandon_pending() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let mut fut = core::pin::pin!(pending::<()>().abort_on_abandon(token));
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    drop(wg);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(Abandoned)));
+    assert!(fut.group_token().is_none());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_abort_on_abandon_in_another_thread() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = WaitGroup::new();
+    let fut = pending::<()>().abort_on_abandon(token.into_token());
+    async move {
+        assert_eq!(fut.await, Err(Abandoned));
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    drop(wg);
+    bg_wg.await;
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_cancelled_abandoned_unregisters() {
+    let wake = StdArc::new(NoopWake);
+    let waker = Waker::from(wake.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let token = token.into_token();
+
+    for _ in 0..3 {
+        let mut abandoned = pin!(token.abandoned());
+        assert_eq!(abandoned.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(abandoned.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(StdArc::strong_count(&wake), 3);
+    }
+    assert_eq!(StdArc::strong_count(&wake), 2);
+
+    for _ in 0..3 {
+        let mut fut = pin!(pending::<()>().abort_on_abandon(token.clone()));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(StdArc::strong_count(&wake), 3);
+    }
+    assert_eq!(StdArc::strong_count(&wake), 2);
+
+    let fut = pending::<()>().abort_on_abandon(token.clone());
+    let mut fut = pin!(fut);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(StdArc::strong_count(&wake), 3);
+
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+    assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+    assert_eq!(StdArc::strong_count(&wake), 4);
+    drop(wg);
+    assert_eq!(StdArc::strong_count(&wake), 2);
+    assert_eq!(fut.poll(&mut cx), Poll::Ready(Err(Abandoned)));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_reusable_wg_reset_drops_abandon_wakers() {
+    let wake = StdArc::new(NoopWake);
+    let waker = Waker::from(wake.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (mut wg, mut factory) = ReusableWaitGroup::new();
+
+    for _ in 0..3 {
+        let token = factory.into_token();
+        assert_eq!(token.poll_abandoned(&mut cx), Poll::Pending);
+        assert_eq!(StdArc::strong_count(&wake), 3);
+        token.release();
+        factory = wg.reset().unwrap();
+        assert_eq!(StdArc::strong_count(&wake), 2);
+    }
+}
+
+#[cfg(not(loom))]
+#[test]
+#[should_panic = "polled after completion"]
+fn test_abort_on_abandon_polled_after_ready() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (_wg, token) = WaitGroup::new();
+    let mut fut = pin!(ready(42).abort_on_abandon(token.into_token()));
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(42)));
+    let _ = fut.as_mut().poll(&mut cx);
+}
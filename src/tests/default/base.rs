@@ -3,9 +3,13 @@ use core::{
     task::{Context, Poll},
 };
 
+use alloc::boxed::Box;
 use futures_test::task::new_count_waker;
 
-use crate::{MonoWaitGroup, WaitGroup};
+use crate::{
+    CounterWaitGroup, GroupClosed, GroupTokenAsyncFuncExt, GroupTokenExt, GroupTokenFuncExt,
+    MonoValueWaitGroup, MonoWaitGroup, ReusableWaitGroup, StartupGroupTokenFactory, WaitGroup,
+};
 
 #[cfg_attr(not(loom), test)]
 pub fn test_wg_done() {
@@ -143,3 +147,452 @@ pub fn test_wg_drop_early() {
     token.release();
     assert_eq!(counter.get(), 0);
 }
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_poll_done_by_ref() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    token.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+    assert_eq!(core::pin::pin!(wg.wait()).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_wait_two_waiters() {
+    let (waker_a, counter_a) = new_count_waker();
+    let (waker_b, counter_b) = new_count_waker();
+    let (waker_c, counter_c) = new_count_waker();
+    let (wg, token) = WaitGroup::new();
+    let mut wait_a = core::pin::pin!(wg.wait());
+    let mut wait_b = core::pin::pin!(wg.wait());
+    let mut wait_c = Box::pin(wg.wait());
+    assert_eq!(
+        wait_a.as_mut().poll(&mut Context::from_waker(&waker_a)),
+        Poll::Pending
+    );
+    assert_eq!(
+        wait_b.as_mut().poll(&mut Context::from_waker(&waker_b)),
+        Poll::Pending
+    );
+    assert_eq!(
+        wait_c.as_mut().poll(&mut Context::from_waker(&waker_c)),
+        Poll::Pending
+    );
+    drop(wait_c);
+    token.release();
+    assert_eq!(counter_a.get(), 1);
+    assert_eq!(counter_b.get(), 1);
+    assert_eq!(counter_c.get(), 0);
+    assert_eq!(
+        wait_a.poll(&mut Context::from_waker(&waker_a)),
+        Poll::Ready(())
+    );
+    assert_eq!(
+        wait_b.poll(&mut Context::from_waker(&waker_b)),
+        Poll::Ready(())
+    );
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_poll_done_by_ref() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let mut wait = core::pin::pin!(wg.wait());
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+    token.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_outstanding_and_snapshot() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, factory) = WaitGroup::new();
+    assert_eq!(wg.outstanding(), 1);
+
+    let token_a = factory.into_token();
+    let token_b = token_a.clone();
+    assert_eq!(wg.outstanding(), 2);
+    assert_eq!(token_a.outstanding(), 2);
+
+    let snapshot = wg.snapshot();
+    assert_eq!(snapshot.outstanding, 2);
+    assert!(!snapshot.waker_registered);
+    assert!(!snapshot.done);
+
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert!(wg.snapshot().waker_registered);
+
+    token_a.release();
+    assert_eq!(token_b.outstanding(), 1);
+    let snapshot = wg.snapshot();
+    assert_eq!(snapshot.outstanding, 1);
+    assert!(snapshot.waker_registered);
+    assert!(!snapshot.done);
+
+    token_b.release();
+    assert_eq!(counter.get(), 1);
+    let snapshot = wg.snapshot();
+    assert_eq!(snapshot.outstanding, 0);
+    assert!(!snapshot.waker_registered);
+    assert!(snapshot.done);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_reusable_wg_reset() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mut wg, factory) = ReusableWaitGroup::new();
+    let token = factory.into_token();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert!(wg.reset().is_none());
+    assert_eq!(wg.generation(), 0);
+
+    token.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(Pin::new(&mut wg).poll(&mut cx), Poll::Ready(()));
+
+    let factory = wg.reset().unwrap();
+    assert_eq!(wg.generation(), 1);
+    assert_eq!(wg.outstanding(), 1);
+    assert!(!wg.snapshot().waker_registered);
+    assert_eq!(Pin::new(&mut wg).poll(&mut cx), Poll::Pending);
+
+    let (token_a, token_b) = factory.scope(|token| (token.clone(), token));
+    token_a.release();
+    assert_eq!(Pin::new(&mut wg).poll(&mut cx), Poll::Pending);
+    token_b.release();
+    assert_eq!(counter.get(), 2);
+    assert_eq!(Pin::new(&mut wg).poll(&mut cx), Poll::Ready(()));
+
+    drop(wg.reset().unwrap());
+    assert_eq!(wg.generation(), 2);
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_counter_wg_done() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let wg = CounterWaitGroup::new();
+    assert_eq!(wg.add(2), Ok(()));
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    wg.done();
+    assert_eq!(wg.count(), 1);
+    assert_eq!(wg.add(1), Ok(()));
+    wg.done();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    wg.done();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wg.count(), 0);
+    assert!(wg.is_done());
+    assert_eq!(core::pin::pin!(wg.wait()).poll(&mut cx), Poll::Ready(()));
+    assert_eq!(wg.add(1), Err(GroupClosed));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_counter_wg_wait_on_zero() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let wg = CounterWaitGroup::new();
+    assert!(!wg.is_done());
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+    assert!(wg.is_done());
+    assert_eq!(wg.add(1), Err(GroupClosed));
+    assert_eq!(counter.get(), 0);
+}
+
+#[cfg(not(loom))]
+#[test]
+#[should_panic = "negative counter"]
+fn test_counter_wg_negative() {
+    let wg = CounterWaitGroup::new();
+    wg.add(1).unwrap();
+    wg.done();
+    wg.done();
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_value_wg_complete() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoValueWaitGroup::new();
+    let mut rx = core::pin::pin!(wg);
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Pending);
+    token.complete(alloc::string::String::from("value"));
+    assert_eq!(counter.get(), 1);
+    assert!(rx.is_done());
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Ready(Some("value".into())));
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Ready(None));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_value_wg_release() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoValueWaitGroup::<u8>::new();
+    let mut rx = core::pin::pin!(wg);
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Pending);
+    token.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Ready(None));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_value_wg_drop_before_send() {
+    let (wg, token) = MonoValueWaitGroup::new();
+    drop(wg);
+    token.complete(alloc::boxed::Box::new(1));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_is_abandoned() {
+    let (wg, token) = WaitGroup::new();
+    let token = token.into_token();
+    let token_cloned = token.clone();
+    assert!(!token.is_abandoned());
+    drop(wg);
+    assert!(token.is_abandoned());
+    assert!(token_cloned.is_abandoned());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_is_abandoned() {
+    let (wg, token) = MonoWaitGroup::new();
+    assert!(!token.is_abandoned());
+    drop(wg);
+    assert!(token.is_abandoned());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_child_done() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let token = token.into_token();
+    let (child_wg, child_token) = token.child();
+    let child_token = child_token.into_token();
+    assert_eq!(wg.outstanding(), 2);
+    token.release();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(child_wg.poll_done(&mut cx), Poll::Pending);
+    child_token.release();
+    assert_eq!(counter.get(), 2);
+    assert_eq!(child_wg.poll_done(&mut cx), Poll::Ready(()));
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_child_nested() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let (child_wg, child_token) = token.scope(|token| token.child());
+    let (grandchild_wg, grandchild_token) = child_token.scope(|token| token.child());
+    drop(child_wg);
+    drop(grandchild_wg);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    grandchild_token.release();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_child_drop_parent_wg() {
+    let (wg, token) = WaitGroup::new();
+    let (child_wg, child_token) = token.scope(|token| token.child());
+    drop(wg);
+    child_token.release();
+    assert!(child_wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_release_on_start() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let mut fut = core::pin::pin!(core::future::pending::<()>().release_on_start(token));
+    assert!(fut.group_token().is_some());
+    assert!(!wg.is_done());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert!(fut.group_token().is_none());
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_startup_factory_track() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (started, finished, factory) = StartupGroupTokenFactory::new();
+    let (mut tx, rx) = MonoWaitGroup::new();
+    let mut fut = core::pin::pin!(factory.track(async move { rx.release() }));
+    factory.release();
+    assert!(!started.is_done());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert!(started.is_done());
+    assert!(finished.is_done());
+    assert_eq!(Pin::new(&mut tx).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_release_on_return_not_send() {
+    let (wg, token) = MonoWaitGroup::new();
+    let local = alloc::rc::Rc::new(1);
+    let func = (move || *local + 1).release_on_return(token);
+    assert!(!wg.is_done());
+    assert_eq!(func(), 2);
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_release_on_complete() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let (mut tx, rx) = MonoWaitGroup::new();
+    let value = 1;
+    let mut fut = core::pin::pin!(
+        (async || {
+            Pin::new(&mut tx).await;
+            value + 1
+        })
+        .release_on_complete(token)
+    );
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert!(!wg.is_done());
+    rx.release();
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(2));
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_release_composite_tokens() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mono_wg, mono_token) = MonoWaitGroup::new();
+    let (wg, factory) = WaitGroup::new();
+    let token = factory.into_token();
+    let tokens = (
+        mono_token,
+        alloc::vec![token.clone(), token.clone()],
+        Some(token.clone()),
+        [token],
+    );
+    let mut fut = core::pin::pin!(async {}.release_on_ready(tokens));
+    assert!(!mono_wg.is_done());
+    assert_eq!(wg.outstanding(), 4);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert!(mono_wg.is_done());
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_with_timeout() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = WaitGroup::new();
+    let (timer, timer_token) = MonoWaitGroup::new();
+    let mut fut = core::pin::pin!(wg.with_timeout(timer));
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+    timer_token.release();
+    let Poll::Ready(Err(timed_out)) = fut.as_mut().poll(&mut cx) else {
+        panic!("timer should fire first");
+    };
+    let wg = timed_out.into_inner();
+    assert!(!wg.is_done());
+    token.release();
+    let mut fut = core::pin::pin!(wg.with_timeout(core::future::pending()));
+    assert!(matches!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(()))));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_with_timeout_done_first() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    token.release();
+    let mut fut = core::pin::pin!(wg.with_timeout(core::future::ready(())));
+    assert!(matches!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(()))));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_into_waker() {
+    let (wg, factory) = WaitGroup::new();
+    let waker = factory.into_token().into_waker();
+    let waker_cloned = waker.clone();
+    assert_eq!(wg.outstanding(), 1);
+    waker.wake_by_ref();
+    assert!(wg.is_done());
+    waker.wake();
+    waker_cloned.wake_by_ref();
+    drop(waker_cloned);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_into_waker_drop() {
+    let (wg, factory) = WaitGroup::new();
+    let waker = factory.into_token().into_waker();
+    let waker_cloned = waker.clone();
+    drop(waker);
+    assert!(!wg.is_done());
+    drop(waker_cloned);
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_wg_child_into_waker() {
+    let (wg, factory) = WaitGroup::new();
+    let (child_wg, child_factory) = factory.scope(|token| token.child());
+    child_factory.into_token().into_waker().wake();
+    assert!(child_wg.is_done());
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_into_waker() {
+    let (wg, token) = MonoWaitGroup::new();
+    let waker = token.into_waker();
+    let waker_cloned = waker.clone();
+    waker_cloned.wake_by_ref();
+    assert!(wg.is_done());
+    waker_cloned.wake();
+    waker.wake_by_ref();
+    drop(waker);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn test_mono_wg_into_waker_many_clones() {
+    let (wg, token) = MonoWaitGroup::new();
+    let waker = token.into_waker();
+    let clones: alloc::vec::Vec<_> = core::iter::repeat_with(|| waker.clone())
+        .take(1000)
+        .collect();
+    drop(waker);
+    assert!(!wg.is_done());
+    drop(clones);
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_mono_wg_into_waker_drop() {
+    let (wg, token) = MonoWaitGroup::new();
+    let waker = token.into_waker();
+    let waker_cloned = waker.clone();
+    drop(waker);
+    assert!(!wg.is_done());
+    drop(waker_cloned);
+    assert!(wg.is_done());
+
+    let (wg, token) = MonoWaitGroup::new();
+    let waker = token.into_waker();
+    drop(wg);
+    drop(waker.clone());
+    drop(waker);
+}
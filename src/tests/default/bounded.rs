@@ -0,0 +1,184 @@
+use core::{
+    pin::pin,
+    task::{Context, Poll},
+};
+
+use alloc::boxed::Box;
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    BoundedWaitGroup, GroupTokenExt, MonoWaitGroup,
+    tests::utils::{Arc, FutureTestExt},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_bounded_wg_acquire() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, factory) = BoundedWaitGroup::new(3);
+    let token_a = factory.try_acquire(2).unwrap();
+    assert_eq!(factory.weight(), 2);
+    assert!(factory.try_acquire(2).is_none());
+
+    let mut acquire = Box::pin(factory.acquire(2));
+    assert!(acquire.as_mut().poll(&mut cx).is_pending());
+    token_a.release();
+    assert_eq!(counter.get(), 1);
+    let Poll::Ready(token_b) = acquire.as_mut().poll(&mut cx) else {
+        panic!("capacity is not released");
+    };
+    assert_eq!(token_b.weight(), 2);
+
+    drop(acquire);
+    factory.release();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    token_b.release();
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_bounded_wg_drop_factory_first() {
+    let (wg, factory) = BoundedWaitGroup::new(1);
+    let token = factory.try_acquire(1).unwrap();
+    drop(factory);
+    assert!(!wg.is_done());
+    drop(token);
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_bounded_wg_acquire_multiple_threads() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, factory) = BoundedWaitGroup::new(1);
+    let factory = Arc::new(factory);
+    let factory_cloned = factory.clone();
+    async move {
+        for _ in 0..2 {
+            let token = factory_cloned.acquire(1).await;
+            async move {}.release_on_ready(token).run_in_background();
+        }
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    bg_wg.await;
+    drop(factory);
+    wg.await;
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_bounded_wg_acquire_fifo() {
+    let (waker_large, counter_large) = new_count_waker();
+    let (waker_small, counter_small) = new_count_waker();
+    let mut cx_large = Context::from_waker(&waker_large);
+    let mut cx_small = Context::from_waker(&waker_small);
+    let (_wg, factory) = BoundedWaitGroup::new(4);
+    let token_a = factory.try_acquire(3).unwrap();
+
+    let mut large = pin!(factory.acquire(4));
+    let mut small = pin!(factory.acquire(1));
+    assert!(large.as_mut().poll(&mut cx_large).is_pending());
+    // The small acquirer fits, but queues behind the large one.
+    assert!(small.as_mut().poll(&mut cx_small).is_pending());
+    assert!(factory.try_acquire(1).is_none());
+
+    token_a.release();
+    assert_eq!(counter_large.get(), 1);
+    assert_eq!(counter_small.get(), 0);
+    assert_eq!(factory.weight(), 4);
+    let Poll::Ready(token_large) = large.as_mut().poll(&mut cx_large) else {
+        panic!("large acquirer is not admitted");
+    };
+    assert!(small.as_mut().poll(&mut cx_small).is_pending());
+
+    token_large.release();
+    assert_eq!(counter_small.get(), 1);
+    let Poll::Ready(token_small) = small.as_mut().poll(&mut cx_small) else {
+        panic!("small acquirer is not admitted");
+    };
+    assert_eq!(token_small.weight(), 1);
+    assert_eq!(factory.weight(), 1);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_bounded_wg_cancel_acquire() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (_wg, factory) = BoundedWaitGroup::new(2);
+    let token = factory.try_acquire(1).unwrap();
+
+    let mut large = Box::pin(factory.acquire(2));
+    let mut small = pin!(factory.acquire(1));
+    assert!(large.as_mut().poll(&mut cx).is_pending());
+    assert!(small.as_mut().poll(&mut cx).is_pending());
+
+    // Giving up the place of the head admits the next acquirer.
+    drop(large);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(factory.weight(), 2);
+
+    let mut next = Box::pin(factory.acquire(2));
+    assert!(next.as_mut().poll(&mut cx).is_pending());
+    token.release();
+    let Poll::Ready(token_small) = small.as_mut().poll(&mut cx) else {
+        panic!("small acquirer is not admitted");
+    };
+    token_small.release();
+    assert_eq!(counter.get(), 2);
+
+    // Dropping an admitted acquirer returns its room.
+    drop(next);
+    assert_eq!(factory.weight(), 0);
+    assert!(factory.try_acquire(2).is_some());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_bounded_wg_admit_many() {
+    let (waker_a, counter_a) = new_count_waker();
+    let (waker_b, counter_b) = new_count_waker();
+    let mut cx_a = Context::from_waker(&waker_a);
+    let mut cx_b = Context::from_waker(&waker_b);
+    let (_wg, factory) = BoundedWaitGroup::new(2);
+    let token = factory.try_acquire(2).unwrap();
+
+    let mut acquire_a = pin!(factory.acquire(1));
+    let mut acquire_b = pin!(factory.acquire(1));
+    assert!(acquire_a.as_mut().poll(&mut cx_a).is_pending());
+    assert!(acquire_b.as_mut().poll(&mut cx_b).is_pending());
+
+    // Both acquirers are admitted, and woken one after another.
+    token.release();
+    assert_eq!(factory.weight(), 2);
+    assert_eq!(counter_a.get(), 1);
+    assert_eq!(counter_b.get(), 0);
+    let Poll::Ready(token_a) = acquire_a.as_mut().poll(&mut cx_a) else {
+        panic!("first acquirer is not admitted");
+    };
+    assert_eq!(counter_b.get(), 1);
+    let Poll::Ready(token_b) = acquire_b.as_mut().poll(&mut cx_b) else {
+        panic!("second acquirer is not admitted");
+    };
+    assert_eq!(token_a.weight() + token_b.weight(), factory.weight());
+}
+
+#[cfg(not(loom))]
+#[test]
+#[should_panic = "polled after completion"]
+fn test_bounded_wg_acquire_polled_after_ready() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (_wg, factory) = BoundedWaitGroup::new(2);
+    let mut acquire = pin!(factory.acquire(1));
+    let Poll::Ready(_token) = acquire.as_mut().poll(&mut cx) else {
+        panic!("acquirer is not admitted");
+    };
+    let _ = acquire.as_mut().poll(&mut cx);
+}
+
+#[cfg(not(loom))]
+#[test]
+#[should_panic = "weight must be nonzero"]
+fn test_bounded_wg_acquire_zero() {
+    let (_wg, factory) = BoundedWaitGroup::new(1);
+    let _ = factory.try_acquire(0);
+}
@@ -0,0 +1,93 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use alloc::vec;
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    AllErrors, ErrorWaitGroup, GroupTokenExt, MonoWaitGroup,
+    tests::utils::{Arc, FutureTestExt, SharedData},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_error_wg_ok() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, factory) = ErrorWaitGroup::<u8>::new();
+    let mut wg = core::pin::pin!(wg);
+    let (token_a, token_b) = factory.scope(|token| (token.clone(), token));
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Pending);
+    token_a.release();
+    token_b.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_error_wg_first_error() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, factory) = ErrorWaitGroup::<u8>::new();
+    let mut wg = core::pin::pin!(wg);
+    let (token_a, token_b) = factory.scope(|token| (token.clone(), token));
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Pending);
+    token_a.fail(1);
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Pending);
+    token_b.report(2);
+    token_b.fail(3);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Ready(Err(1)));
+    assert_eq!(wg.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_error_wg_all_errors() {
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (mut wg, factory) = ErrorWaitGroup::<u8, AllErrors>::new();
+    let (token_a, token_b) = factory.scope(|token| (token.clone(), token));
+    token_a.fail(1);
+    token_b.report(2);
+    assert_eq!(Pin::new(&mut wg).poll(&mut cx), Poll::Pending);
+    token_b.fail(3);
+    assert_eq!(
+        Pin::new(&mut wg).poll(&mut cx),
+        Poll::Ready(Err(vec![1, 2, 3]))
+    );
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_error_wg_drop_before_send() {
+    let (wg, factory) = ErrorWaitGroup::<alloc::boxed::Box<u8>, AllErrors>::new();
+    drop(wg);
+    factory.into_token().fail(alloc::boxed::Box::new(1));
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_error_wg_release_on_ready() {
+    let canary = Arc::new(SharedData::new());
+    let inspector = canary.clone();
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, factory) = ErrorWaitGroup::<u8>::new();
+    async move {
+        assert_eq!(wg.await, Err(2));
+        canary.store();
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    let (token_a, token_b) = factory.scope(|token| (token.clone(), token));
+    assert_eq!(
+        async { Ok::<_, u8>(1) }.release_on_ready(token_a).await,
+        Some(1)
+    );
+    assert!(!inspector.load());
+    assert_eq!(
+        async { Err::<u8, _>(2) }.release_on_ready(token_b).await,
+        None
+    );
+    bg_wg.await;
+    assert!(inspector.load());
+}
@@ -1,12 +1,21 @@
-use core::pin::Pin;
+use core::{future::poll_fn, pin::Pin, task::Poll};
 
 use alloc::boxed::Box;
 
 use crate::{
-    GroupTokenExt, MonoWaitGroup, WaitGroup,
+    CounterWaitGroup, GroupTokenExt, MonoValueWaitGroup, MonoWaitGroup, ReusableWaitGroup,
+    StartupGroupTokenFactory, WaitGroup,
     tests::utils::{Arc, FutureTestExt, SharedData},
 };
 
+/// Number of tokens released one after another. Fewer under loom with the
+/// `stats` feature, where each token adds its statistics updates to the model.
+const TOKENS: usize = if cfg!(all(loom, feature = "stats")) {
+    20
+} else {
+    100
+};
+
 #[cfg_attr(not(loom), futures_test::test)]
 pub async fn test_wg_await_background() {
     let canary = Arc::new(SharedData::new());
@@ -134,7 +143,7 @@ pub async fn test_wg_await_multiple_repeat_n() {
 
     assert!(!inspector.load());
 
-    for token in core::iter::repeat_n(factory.into_token(), 100) {
+    for token in core::iter::repeat_n(factory.into_token(), TOKENS) {
         assert!(!inspector.load());
         token.release();
     }
@@ -160,7 +169,7 @@ pub async fn test_wg_await_multiple_repeat_with() {
 
     for token in factory
         .scope(|token| core::iter::repeat_with(move || token.clone()))
-        .take(100)
+        .take(TOKENS)
     {
         assert!(!inspector.load());
         token.release();
@@ -185,7 +194,7 @@ pub async fn test_wg_await_pin_multiple_repeat_n() {
 
     assert!(!inspector.load());
 
-    for token in core::iter::repeat_n(factory.into_token(), 100) {
+    for token in core::iter::repeat_n(factory.into_token(), TOKENS) {
         assert!(!inspector.load());
         token.release();
     }
@@ -213,7 +222,7 @@ pub async fn test_wg_await_pin_multiple_repeat_with() {
 
     for token in factory
         .scope(|token| core::iter::repeat_with(move || token.clone()))
-        .take(100)
+        .take(TOKENS)
     {
         assert!(!inspector.load());
         token.release();
@@ -230,6 +239,11 @@ const _TESTING_THREADS: usize = 16;
 #[cfg(loom)]
 const _TESTING_THREADS: usize = 2;
 
+#[cfg(not(loom))]
+const _TESTING_ROUNDS: usize = 16;
+#[cfg(loom)]
+const _TESTING_ROUNDS: usize = 2;
+
 #[cfg_attr(not(loom), futures_test::test)]
 pub async fn test_mono_wg_await_pin_multiple_threads() {
     let canary = Arc::new(SharedData::new());
@@ -301,3 +315,145 @@ pub async fn test_wg_await_pin_multiple_threads() {
     assert!(inspector.load());
     assert!(bg_wg.is_done());
 }
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_wg_wait_by_ref_multiple_threads() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = WaitGroup::new();
+    let wg = Arc::new(wg);
+    let wg_cloned = wg.clone();
+    async move {
+        let _ = poll_fn(|cx| Poll::Ready(wg_cloned.poll_done(cx))).await;
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    assert_eq!(
+        poll_fn(|cx| Poll::Ready(wg.poll_done(cx))).await,
+        Poll::Pending
+    );
+    bg_wg.await;
+    token.release();
+    wg.wait().await;
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_wg_wait_two_waiters_multiple_threads() {
+    let (bg_wg, bg_factory) = WaitGroup::new();
+    let (wg, token) = WaitGroup::new();
+    let wg = Arc::new(wg);
+    bg_factory.scope(|bg_token| {
+        for _ in 0..2 {
+            let wg = wg.clone();
+            async move { wg.wait().await }
+                .release_on_ready(bg_token.clone())
+                .run_in_background();
+        }
+    });
+    token.release();
+    bg_wg.await;
+    assert!(wg.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_reusable_wg_rounds_background() {
+    let (mut wg, mut factory) = ReusableWaitGroup::new();
+    for round in 0.._TESTING_ROUNDS {
+        let (bg_wg, bg_token) = MonoWaitGroup::new();
+        async move {
+            factory.release();
+        }
+        .release_on_ready(bg_token)
+        .run_in_background();
+        (&mut wg).await;
+        assert_eq!(wg.generation(), round);
+        factory = wg.reset().unwrap();
+        bg_wg.await;
+    }
+    factory.release();
+    wg.await;
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_counter_wg_await_multiple_threads() {
+    let wg = Arc::new(CounterWaitGroup::new());
+    wg.add(_TESTING_THREADS).unwrap();
+    for _ in 0.._TESTING_THREADS {
+        let wg = wg.clone();
+        async move {
+            wg.done();
+        }
+        .run_in_background();
+    }
+    wg.wait().await;
+    assert_eq!(wg.count(), 0);
+    assert!(wg.add(1).is_err());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_mono_value_wg_await_background() {
+    let (wg, token) = MonoValueWaitGroup::new();
+    async move {
+        token.complete(alloc::vec![1, 2, 3]);
+    }
+    .run_in_background();
+    assert_eq!(wg.await, Some(alloc::vec![1, 2, 3]));
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_wg_child_await_background() {
+    let canary = Arc::new(SharedData::new());
+    let inspector = canary.clone();
+    let (wg, token) = WaitGroup::new();
+    let (child_wg, child_token) = token.scope(|token| token.child());
+    async move {
+        canary.store();
+    }
+    .release_on_ready(child_token)
+    .run_in_background();
+    wg.await;
+    assert!(inspector.load());
+    assert!(child_wg.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_startup_factory_background() {
+    let (started, finished, factory) = StartupGroupTokenFactory::new();
+    let (wg, token) = MonoWaitGroup::new();
+    factory
+        .track(async move {
+            wg.await;
+        })
+        .run_in_background();
+    factory.release();
+    started.await;
+    assert!(!finished.is_done());
+    token.release();
+    finished.await;
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_mono_wg_into_waker_background() {
+    let (wg, token) = MonoWaitGroup::new();
+    let waker = token.into_waker();
+    let waker_cloned = waker.clone();
+    async move {
+        waker_cloned.wake();
+    }
+    .run_in_background();
+    drop(waker);
+    wg.await;
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_wg_into_waker_background() {
+    let (wg, factory) = WaitGroup::new();
+    let waker = factory.into_token().into_waker();
+    let waker_cloned = waker.clone();
+    async move {
+        waker_cloned.wake();
+    }
+    .run_in_background();
+    drop(waker);
+    wg.await;
+}
@@ -0,0 +1,89 @@
+use core::task::{Context, Poll};
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    Gate, GroupClosed, GroupTokenExt,
+    tests::utils::{Arc, FutureTestExt},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_gate_close_before_enter() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let gate = Gate::new();
+    assert_eq!(gate.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(gate.outstanding(), 0);
+    gate.close();
+    assert!(gate.is_closed());
+    assert_eq!(counter.get(), 1);
+    assert_eq!(gate.poll_done(&mut cx), Poll::Ready(()));
+    assert_eq!(gate.enter().err(), Some(GroupClosed));
+    gate.close();
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_gate_enter_and_close() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let gate = Gate::new();
+    let token_a = gate.enter().unwrap();
+    let token_b = gate.enter().unwrap();
+    assert_eq!(gate.outstanding(), 2);
+
+    token_a.release();
+    assert_eq!(gate.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(gate.outstanding(), 1);
+
+    gate.close();
+    assert!(gate.enter().is_err());
+    assert_eq!(gate.outstanding(), 1);
+    assert_eq!(gate.poll_done(&mut cx), Poll::Pending);
+
+    token_b.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(gate.outstanding(), 0);
+    assert_eq!(gate.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_gate_drop_while_open() {
+    let gate = Gate::new();
+    let token = gate.enter().unwrap();
+    drop(gate);
+    token.release();
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_gate_enter_in_another_thread() {
+    let gate = Arc::new(Gate::new());
+    let gate_cloned = gate.clone();
+    async move {
+        if let Ok(token) = gate_cloned.enter() {
+            async {}.release_on_ready(token).run_in_background();
+        }
+    }
+    .run_in_background();
+    gate.close();
+    gate.wait().await;
+    assert!(gate.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_gate_enter_concurrently_with_close() {
+    let gate = Arc::new(Gate::new());
+    let gate_entering = gate.clone();
+    async move {
+        if let Ok(token) = gate_entering.enter() {
+            token.release();
+        }
+    }
+    .run_in_background();
+    let gate_closing = gate.clone();
+    async move { gate_closing.close() }.run_in_background();
+    if let Ok(token) = gate.enter() {
+        token.release();
+    }
+    gate.wait().await;
+    assert!(gate.enter().is_err());
+}
@@ -0,0 +1,75 @@
+use core::task::{Context, Poll};
+
+use alloc::{string::ToString, vec, vec::Vec};
+
+use futures_test::task::noop_context;
+
+use crate::{GroupTokenExt, LabeledWaitGroup, tests::utils::FutureTestExt};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_labeled_wg_outstanding_labels() {
+    let (wg, factory) = LabeledWaitGroup::new();
+    let shard_7 = factory.token("shard-7");
+    let shard_12 = factory.token("shard-12");
+    let shard_7_cloned = shard_7.clone();
+    assert_eq!(shard_7_cloned.label(), "shard-7");
+    assert_eq!(
+        wg.outstanding_labels(),
+        vec!["shard-7", "shard-12", "shard-7"]
+    );
+
+    shard_7.release();
+    assert_eq!(wg.outstanding_labels(), vec!["shard-12", "shard-7"]);
+    factory.release();
+    shard_12.release();
+    assert!(!wg.is_done());
+    shard_7_cloned.release();
+    assert!(wg.is_done());
+    assert!(wg.outstanding_labels().is_empty());
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_labeled_wg_waiting_on() {
+    let mut cx: Context<'_> = noop_context();
+    let (wg, factory) = LabeledWaitGroup::new();
+    assert_eq!(wg.waiting_on().to_string(), "waiting on the factory");
+
+    let shard_7 = factory.token(7);
+    let shard_12 = factory.token(12);
+    assert_eq!(wg.waiting_on().to_string(), "waiting on 7, 12, the factory");
+    factory.release();
+    let waiting_on = wg.waiting_on();
+    assert_eq!(waiting_on.labels, vec![7, 12]);
+    assert!(!waiting_on.factory);
+    assert_eq!(waiting_on.to_string(), "waiting on 7, 12");
+
+    drop((shard_7, shard_12));
+    assert_eq!(wg.poll_done(&mut cx), Poll::Ready(()));
+    assert_eq!(wg.waiting_on().to_string(), "waiting on nothing");
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_labeled_wg_with_timeout() {
+    let (wg, factory) = LabeledWaitGroup::new();
+    let tokens: Vec<_> = (0..3).map(|id| factory.token(id)).collect();
+    factory.release();
+
+    let wg = wg.with_timeout(async {}).await.unwrap_err().into_inner();
+    assert_eq!(wg.outstanding_labels(), vec![0, 1, 2]);
+
+    drop(tokens);
+    wg.with_timeout(core::future::pending()).await.unwrap();
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_labeled_wg_multiple_threads() {
+    let (wg, factory) = LabeledWaitGroup::new();
+    let token = factory.token(0);
+    async {}
+        .release_on_ready(factory.token(1))
+        .run_in_background();
+    factory.release();
+    token.release();
+    wg.wait().await;
+    assert!(wg.outstanding_labels().is_empty());
+}
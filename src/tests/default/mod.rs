@@ -1,7 +1,23 @@
+mod abandon;
 mod base;
+mod bounded;
+mod error_group;
 mod futures;
+mod gate;
+mod labeled;
 mod panic;
+mod shared;
+mod shutdown;
+#[cfg(any(feature = "futures-core", feature = "futures-io"))]
+mod stream;
+mod tracker;
 mod twin_ref;
 
+#[cfg(any(feature = "futures-core", feature = "futures-io"))]
 #[cfg_attr(not(loom), allow(unused_imports))]
-pub(super) use self::{base::*, futures::*, panic::*, twin_ref::*};
+pub(super) use self::stream::*;
+#[cfg_attr(not(loom), allow(unused_imports))]
+pub(super) use self::{
+    abandon::*, base::*, bounded::*, error_group::*, futures::*, gate::*, labeled::*, panic::*,
+    shared::*, shutdown::*, tracker::*, twin_ref::*,
+};
@@ -0,0 +1,196 @@
+use core::{
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+};
+
+use alloc::sync::Arc as StdArc;
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    GroupTokenExt, MonoWaitGroup, SharedWaitGroup,
+    tests::utils::{Arc, FutureTestExt, NoopWake, SharedData},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_done() {
+    let (waker_a, counter_a) = new_count_waker();
+    let (waker_b, counter_b) = new_count_waker();
+
+    let (wg, token) = SharedWaitGroup::new();
+    let mut wg_a = wg.clone();
+    let mut wg_b = wg;
+
+    let mut cx_a = Context::from_waker(&waker_a);
+    let mut cx_b = Context::from_waker(&waker_b);
+    assert_eq!(Pin::new(&mut wg_a).poll(&mut cx_a), Poll::Pending);
+    assert_eq!(Pin::new(&mut wg_b).poll(&mut cx_b), Poll::Pending);
+
+    token.release();
+
+    assert_eq!(counter_a.get(), 1);
+    assert_eq!(counter_b.get(), 1);
+    assert_eq!(Pin::new(&mut wg_a).poll(&mut cx_a), Poll::Ready(()));
+    assert_eq!(Pin::new(&mut wg_b).poll(&mut cx_b), Poll::Ready(()));
+    assert_eq!(Pin::new(&mut wg_b).poll(&mut cx_b), Poll::Ready(()));
+    assert_eq!(counter_a.get(), 1);
+    assert_eq!(counter_b.get(), 1);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_dedup_waker() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let (wg, token) = SharedWaitGroup::new();
+    let mut wg_a = wg.clone();
+    let mut wg_b = wg;
+    let (token_a, token_b) = token.scope(|token| (token.clone(), token));
+
+    assert_eq!(Pin::new(&mut wg_a).poll(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut wg_a).poll(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut wg_b).poll(&mut cx), Poll::Pending);
+    token_a.release();
+    assert_eq!(Pin::new(&mut wg_b).poll(&mut cx), Poll::Pending);
+    token_b.release();
+
+    assert_eq!(counter.get(), 1);
+    assert_eq!(Pin::new(&mut wg_a).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_send_before_poll() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = SharedWaitGroup::new();
+    token.release();
+    let mut rx = core::pin::pin!(wg.clone());
+    assert_eq!(rx.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert!(wg.is_done());
+    assert_eq!(counter.get(), 0);
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_drop_before_send() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = SharedWaitGroup::new();
+    let mut wg_cloned = wg.clone();
+    assert_eq!(Pin::new(&mut wg_cloned).poll(&mut cx), Poll::Pending);
+    drop(wg_cloned);
+    drop(wg);
+    token.release();
+    assert_eq!(counter.get(), 0);
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_shared_wg_await_background() {
+    let canary_a = Arc::new(SharedData::new());
+    let canary_b = Arc::new(SharedData::new());
+    let (inspector_a, inspector_b) = (canary_a.clone(), canary_b.clone());
+    let (bg_wg, bg_factory) = SharedWaitGroup::new();
+    let (wg, token) = SharedWaitGroup::new();
+    let wg_cloned = wg.clone();
+    bg_factory.scope(|bg_token| {
+        async move {
+            wg.await;
+            canary_a.store();
+        }
+        .release_on_ready(bg_token.clone())
+        .run_in_background();
+        async move {
+            wg_cloned.await;
+            canary_b.store();
+        }
+        .release_on_ready(bg_token)
+        .run_in_background();
+    });
+    assert!(!inspector_a.load());
+    assert!(!inspector_b.load());
+    token.release();
+    bg_wg.await;
+    assert!(inspector_a.load());
+    assert!(inspector_b.load());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_shared_wg_await_in_place() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = SharedWaitGroup::new();
+    let wg_cloned = wg.clone();
+    async move {
+        wg_cloned.await;
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    token.release();
+    wg.await;
+    bg_wg.await;
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_cancelled_wait_unregisters() {
+    let wake = StdArc::new(NoopWake);
+    let waker = Waker::from(wake.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = SharedWaitGroup::new();
+
+    for _ in 0..3 {
+        let mut wait = pin!(wg.wait());
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(StdArc::strong_count(&wake), 3);
+    }
+    assert_eq!(StdArc::strong_count(&wake), 2);
+
+    let mut wg_cloned = wg.clone();
+    assert_eq!(Pin::new(&mut wg_cloned).poll(&mut cx), Poll::Pending);
+    assert_eq!(StdArc::strong_count(&wake), 3);
+    drop(wg_cloned);
+    assert_eq!(StdArc::strong_count(&wake), 2);
+
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    assert_eq!(StdArc::strong_count(&wake), 3);
+    token.release();
+    assert_eq!(StdArc::strong_count(&wake), 2);
+    assert_eq!(pin!(wg.wait()).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shared_wg_wait_wakes_once() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = SharedWaitGroup::new();
+
+    let mut wait_a = pin!(wg.wait());
+    let mut wait_b = pin!(wg.wait());
+    assert_eq!(wait_a.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(wait_b.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(wg.poll_done(&mut cx), Poll::Pending);
+    token.release();
+
+    assert_eq!(counter.get(), 1);
+    assert_eq!(wait_a.poll(&mut cx), Poll::Ready(()));
+    assert_eq!(wait_b.poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_shared_wg_cancel_wait_background() {
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let (wg, token) = SharedWaitGroup::new();
+    let wg_cloned = wg.clone();
+    async move {
+        let mut wait = pin!(wg_cloned.wait());
+        core::future::poll_fn(|cx| {
+            let _ = wait.as_mut().poll(cx);
+            Poll::Ready(())
+        })
+        .await;
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    token.release();
+    wg.wait().await;
+    bg_wg.await;
+}
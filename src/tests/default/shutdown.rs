@@ -0,0 +1,79 @@
+use core::task::{Context, Poll};
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    GroupClosed, GroupTokenExt, MonoWaitGroup, Shutdown,
+    tests::utils::{Arc, FutureTestExt},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shutdown_trigger_and_drain() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let shutdown = Shutdown::new();
+    let token = shutdown.token().unwrap();
+    let token_cloned = token.clone();
+    assert_eq!(shutdown.outstanding(), 2);
+    assert_eq!(token.poll_stopping(&mut cx), Poll::Pending);
+    assert_eq!(shutdown.poll_done(&mut cx), Poll::Pending);
+
+    shutdown.trigger();
+    assert!(shutdown.is_triggered());
+    assert!(token_cloned.is_stopping());
+    assert_eq!(counter.get(), 1);
+    assert_eq!(token.poll_stopping(&mut cx), Poll::Ready(()));
+    assert_eq!(shutdown.token().err(), Some(GroupClosed));
+    assert_eq!(shutdown.poll_done(&mut cx), Poll::Pending);
+
+    token.release();
+    token_cloned.release();
+    assert_eq!(counter.get(), 2);
+    assert_eq!(shutdown.poll_done(&mut cx), Poll::Ready(()));
+    shutdown.trigger();
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_shutdown_drop_controller() {
+    let shutdown = Shutdown::new();
+    let token = shutdown.token().unwrap();
+    drop(shutdown);
+    assert!(token.is_stopping());
+    token.release();
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_shutdown_in_another_thread() {
+    let shutdown = Arc::new(Shutdown::new());
+    let (bg_wg, bg_token) = MonoWaitGroup::new();
+    let token = shutdown.token().unwrap();
+    async move {
+        token.stopping().await;
+        token.release();
+    }
+    .release_on_ready(bg_token)
+    .run_in_background();
+    let shutdown_cloned = shutdown.clone();
+    async move {
+        shutdown_cloned.trigger();
+    }
+    .run_in_background();
+    shutdown.wait().await;
+    assert!(shutdown.is_triggered());
+    bg_wg.await;
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_shutdown_closed_once_stopping() {
+    let shutdown = Arc::new(Shutdown::new());
+    let token = shutdown.token().unwrap();
+    let shutdown_cloned = shutdown.clone();
+    async move {
+        shutdown_cloned.trigger();
+    }
+    .run_in_background();
+    token.stopping().await;
+    assert_eq!(shutdown.token().err(), Some(GroupClosed));
+    token.release();
+    shutdown.wait().await;
+}
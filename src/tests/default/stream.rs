@@ -0,0 +1,97 @@
+use core::{
+    pin::{Pin, pin},
+    task::{Context, Poll},
+};
+
+use futures_test::task::new_count_waker;
+
+use crate::MonoWaitGroup;
+
+#[cfg(feature = "futures-core")]
+struct Countdown(usize);
+
+#[cfg(feature = "futures-core")]
+impl futures_core::Stream for Countdown {
+    type Item = usize;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<usize>> {
+        Poll::Ready(self.0.checked_sub(1).inspect(|n| self.0 = *n))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+struct SliceReader(&'static [u8]);
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for SliceReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let len = buf.len().min(self.0.len());
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(feature = "futures-core")]
+#[cfg_attr(not(loom), test)]
+pub fn test_stream_release_on_end() {
+    use futures_core::Stream;
+
+    use crate::GroupTokenStreamExt;
+
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let mut stream = pin!(Countdown(2).release_on_end(token));
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(0)));
+    assert!(!wg.is_done());
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    assert!(stream.group_token().is_none());
+    assert!(wg.is_done());
+}
+
+#[cfg(feature = "futures-core")]
+#[cfg_attr(not(loom), test)]
+pub fn test_stream_release_on_drop() {
+    use crate::GroupTokenStreamExt;
+
+    let (wg, token) = MonoWaitGroup::new();
+    let stream = Countdown(2).release_on_end(token);
+    assert!(!wg.is_done());
+    drop(stream);
+    assert!(wg.is_done());
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(not(loom), test)]
+pub fn test_async_read_release_on_eof() {
+    use futures_io::AsyncRead;
+
+    use crate::GroupTokenAsyncReadExt;
+
+    let (waker, _) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (wg, token) = MonoWaitGroup::new();
+    let mut reader = pin!(SliceReader(b"abc").release_on_eof(token));
+    let mut buf = [0; 2];
+    let Poll::Ready(Ok(2)) = reader.as_mut().poll_read(&mut cx, &mut buf) else {
+        panic!("unexpected read");
+    };
+    let Poll::Ready(Ok(0)) = reader.as_mut().poll_read(&mut cx, &mut []) else {
+        panic!("unexpected read");
+    };
+    let Poll::Ready(Ok(1)) = reader.as_mut().poll_read(&mut cx, &mut buf) else {
+        panic!("unexpected read");
+    };
+    assert!(!wg.is_done());
+    let Poll::Ready(Ok(0)) = reader.as_mut().poll_read(&mut cx, &mut buf) else {
+        panic!("unexpected read");
+    };
+    assert!(reader.group_token().is_none());
+    assert!(wg.is_done());
+}
@@ -0,0 +1,136 @@
+use core::{
+    pin::{Pin, pin},
+    task::{Context, Poll},
+};
+
+use futures_test::task::new_count_waker;
+
+use crate::{
+    TaskTracker,
+    tests::utils::{Arc, FutureTestExt},
+};
+
+#[cfg_attr(not(loom), test)]
+pub fn test_tracker_track_and_close() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let tracker = TaskTracker::new();
+    assert!(tracker.is_empty());
+    let mut fut_a = pin!(tracker.track_future(async { 1 }));
+    let fut_b = tracker.track_future(async { 2 });
+    assert_eq!(tracker.len(), 2);
+
+    assert!(tracker.close());
+    assert!(!tracker.close());
+    assert!(tracker.is_closed());
+    let mut fut_c = pin!(tracker.track_future(async { 3 }));
+    assert_eq!(tracker.len(), 3);
+    assert_eq!(tracker.poll_done(&mut cx), Poll::Pending);
+
+    assert_eq!(fut_a.as_mut().poll(&mut cx), Poll::Ready(1));
+    assert_eq!(fut_c.as_mut().poll(&mut cx), Poll::Ready(3));
+    assert_eq!(tracker.len(), 1);
+    assert_eq!(counter.get(), 0);
+    drop(fut_b);
+    assert!(tracker.is_empty());
+    assert_eq!(counter.get(), 1);
+    assert_eq!(tracker.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_tracker_close_empty() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let tracker = TaskTracker::new();
+    assert_eq!(tracker.poll_done(&mut cx), Poll::Pending);
+    tracker.close();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(tracker.poll_done(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_tracker_track_after_done() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let tracker = TaskTracker::new();
+    tracker.close();
+    assert!(tracker.is_done());
+
+    let token = tracker.token();
+    let token_cloned = token.clone();
+    assert!(!tracker.is_done());
+    assert_eq!(tracker.len(), 2);
+    let mut wait = tracker.wait();
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Pending);
+    token.release();
+    assert_eq!(counter.get(), 0);
+    drop(tracker);
+    token_cloned.release();
+    assert_eq!(counter.get(), 1);
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_tracker_wait_after_track_after_close() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let tracker = TaskTracker::new();
+    let token = tracker.token();
+    let mut wait = tracker.wait();
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Pending);
+
+    tracker.close();
+    let token_after_close = tracker.token();
+    assert_eq!(tracker.len(), 2);
+    token.release();
+    assert_eq!(counter.get(), 0);
+    token_after_close.release();
+    assert_eq!(counter.get(), 1);
+    assert!(tracker.is_done());
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Ready(()));
+
+    // Waiting resolves once the tracker is done, even if a future is tracked
+    // again afterwards.
+    let mut wait = tracker.wait();
+    let mut fut = pin!(tracker.track_future(async {}));
+    assert!(!tracker.is_done());
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Ready(()));
+    let mut wait = tracker.wait();
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Pending);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert_eq!(counter.get(), 2);
+    assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Ready(()));
+}
+
+#[cfg_attr(not(loom), test)]
+pub fn test_tracker_reopen() {
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let tracker = TaskTracker::new();
+    let token = tracker.token();
+    tracker.close();
+    assert!(tracker.reopen());
+    assert!(!tracker.reopen());
+    assert_eq!(tracker.poll_done(&mut cx), Poll::Pending);
+    token.release();
+    assert_eq!(counter.get(), 0);
+    assert!(!tracker.is_done());
+    tracker.close();
+    assert_eq!(counter.get(), 1);
+    assert!(tracker.is_done());
+}
+
+#[cfg_attr(not(loom), futures_test::test)]
+pub async fn test_tracker_track_in_another_thread() {
+    let tracker = Arc::new(TaskTracker::new());
+    let tracker_cloned = tracker.clone();
+    async move {
+        let fut = tracker_cloned.track_future(async {});
+        tracker_cloned.close();
+        fut.await;
+    }
+    .run_in_background();
+    tracker.wait().await;
+    assert!(tracker.is_closed());
+    assert!(tracker.is_empty());
+}
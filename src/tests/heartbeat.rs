@@ -0,0 +1,55 @@
+#![cfg(all(feature = "std", not(loom)))]
+
+use std::{thread, time::Duration};
+
+use crate::{GroupTokenExt, HeartbeatWaitGroup};
+
+#[test]
+fn test_heartbeat_wg_stalled() {
+    let (wg, factory) = HeartbeatWaitGroup::new();
+    let token_a = factory.into_token();
+    let token_b = token_a.clone();
+    assert_ne!(token_a.id(), token_b.id());
+    assert_eq!(wg.outstanding(), 2);
+    assert!(wg.stalled(Duration::MAX).is_empty());
+    assert_eq!(wg.stalled(Duration::ZERO).len(), 2);
+
+    thread::sleep(Duration::from_millis(50));
+    token_a.heartbeat();
+    let stalled = wg.stalled(Duration::from_millis(25));
+    assert_eq!(stalled.len(), 1);
+    assert_eq!(stalled[0].id, token_b.id());
+    assert!(stalled[0].elapsed >= Duration::from_millis(25));
+
+    token_b.release();
+    assert!(wg.stalled(Duration::from_millis(25)).is_empty());
+    token_a.release();
+    assert!(wg.is_done());
+    assert_eq!(wg.outstanding(), 0);
+}
+
+#[futures_test::test]
+async fn test_heartbeat_wg_release_on_ready() {
+    let (wg, factory) = HeartbeatWaitGroup::new();
+    let token = factory.into_token();
+    let task = async {}.release_on_ready(token.clone());
+    assert_eq!(wg.stalled(Duration::ZERO).len(), 2);
+    token.release();
+    task.await;
+    wg.await;
+}
+
+#[test]
+fn test_heartbeat_wg_watchdog_thread() {
+    let (mut wg, factory) = HeartbeatWaitGroup::new();
+    let token = factory.into_token();
+    let worker = thread::spawn(move || {
+        for _ in 0..3 {
+            thread::sleep(Duration::from_millis(5));
+            token.heartbeat();
+        }
+    });
+    worker.join().unwrap();
+    assert!(wg.stalled(Duration::ZERO).is_empty());
+    futures_executor::block_on(&mut wg);
+}
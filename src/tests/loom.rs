@@ -59,29 +59,117 @@ macro_rules! loom_test_case {
     };
 }
 
+// abandon
+loom_test_case!(test_abort_on_abandon_pending);
+loom_test_case!(test_abort_on_abandon_ready);
+loom_test_case!(test_mono_wg_abandoned);
+loom_test_case!(test_reusable_wg_reset_drops_abandon_wakers);
+loom_test_case!(test_wg_abandoned);
+loom_test_case!(test_wg_abandoned_after_done);
+loom_test_case!(test_wg_cancelled_abandoned_unregisters);
+loom_test_case!(async test_abort_on_abandon_in_another_thread);
+loom_test_case!(async test_mono_wg_abandoned_in_another_thread);
+loom_test_case!(async test_wg_abandoned_in_another_thread);
+
 // base
+loom_test_case!(test_mono_wg_is_abandoned);
+loom_test_case!(test_wg_is_abandoned);
+loom_test_case!(test_mono_value_wg_complete);
+loom_test_case!(test_mono_value_wg_drop_before_send);
+loom_test_case!(test_mono_value_wg_release);
 loom_test_case!(test_mono_wg_done);
 loom_test_case!(test_mono_wg_drop_before_send);
 loom_test_case!(test_mono_wg_send_before_poll);
+loom_test_case!(test_counter_wg_done);
+loom_test_case!(test_counter_wg_wait_on_zero);
+loom_test_case!(test_wg_child_done);
+loom_test_case!(test_wg_child_drop_parent_wg);
+loom_test_case!(test_wg_child_nested);
 loom_test_case!(test_wg_done);
 loom_test_case!(test_wg_done_twice);
 loom_test_case!(test_wg_done_twice_rev);
 loom_test_case!(test_wg_drop_before_send);
 loom_test_case!(test_wg_drop_early);
+loom_test_case!(test_mono_wg_poll_done_by_ref);
+loom_test_case!(test_wg_outstanding_and_snapshot);
 loom_test_case!(test_wg_poll_by_others);
+loom_test_case!(test_wg_poll_done_by_ref);
+loom_test_case!(test_wg_wait_two_waiters);
 loom_test_case!(test_wg_send_before_poll);
+loom_test_case!(test_release_composite_tokens);
+loom_test_case!(test_release_on_complete);
+loom_test_case!(test_release_on_return_not_send);
+loom_test_case!(test_release_on_start);
+loom_test_case!(test_reusable_wg_reset);
+loom_test_case!(test_startup_factory_track);
+loom_test_case!(test_wg_with_timeout);
+loom_test_case!(test_mono_wg_into_waker);
+loom_test_case!(test_mono_wg_into_waker_drop);
+loom_test_case!(test_wg_child_into_waker);
+loom_test_case!(test_wg_into_waker);
+loom_test_case!(test_wg_into_waker_drop);
+loom_test_case!(test_mono_wg_with_timeout_done_first);
+
+// bounded
+loom_test_case!(test_bounded_wg_acquire);
+loom_test_case!(test_bounded_wg_acquire_fifo);
+loom_test_case!(test_bounded_wg_admit_many);
+loom_test_case!(test_bounded_wg_cancel_acquire);
+loom_test_case!(test_bounded_wg_drop_factory_first);
+loom_test_case! {
+    async test_bounded_wg_acquire_multiple_threads,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+
+// error_group
+loom_test_case!(test_error_wg_all_errors);
+loom_test_case!(test_error_wg_drop_before_send);
+loom_test_case!(test_error_wg_first_error);
+loom_test_case!(test_error_wg_ok);
+loom_test_case!(async test_error_wg_release_on_ready);
 
 // futures
+loom_test_case!(async test_mono_value_wg_await_background);
 loom_test_case!(async test_mono_wg_await_background);
 loom_test_case!(async test_mono_wg_pinned_drop_in_another_thread);
 loom_test_case!(async test_wg_await);
 loom_test_case!(async test_wg_await_background);
+loom_test_case! {
+    async test_startup_factory_background,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+loom_test_case!(async test_wg_child_await_background);
+loom_test_case!(async test_mono_wg_into_waker_background);
+loom_test_case!(async test_wg_into_waker_background);
 loom_test_case!(async test_wg_await_background_twice);
 loom_test_case!(async test_wg_await_background_twice_rev);
 loom_test_case!(async test_wg_await_multiple_repeat_n);
 loom_test_case!(async test_wg_await_multiple_repeat_with);
 loom_test_case!(async test_wg_await_pin_multiple_repeat_n);
 loom_test_case!(async test_wg_await_pin_multiple_repeat_with);
+loom_test_case!(async test_wg_wait_by_ref_multiple_threads);
+loom_test_case! {
+    async test_wg_wait_two_waiters_multiple_threads,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+loom_test_case! {
+    async test_counter_wg_await_multiple_threads,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+loom_test_case! {
+    async test_reusable_wg_rounds_background,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
 loom_test_case! {
     async test_mono_wg_await_pin_multiple_threads,
     |builder: &mut loom::model::Builder| {
@@ -95,6 +183,58 @@ loom_test_case! {
     }
 }
 
+// gate
+loom_test_case!(test_gate_close_before_enter);
+loom_test_case!(test_gate_drop_while_open);
+loom_test_case!(test_gate_enter_and_close);
+loom_test_case!(async test_gate_enter_in_another_thread);
+loom_test_case! {
+    async test_gate_enter_concurrently_with_close,
+    |builder: &mut loom::model::Builder| {
+        // `close` spins until the concurrent `enter` calls are done.
+        builder.preemption_bound = Some(1);
+        builder.max_branches = 10_000;
+    }
+}
+
+// labeled
+loom_test_case!(test_labeled_wg_outstanding_labels);
+loom_test_case!(test_labeled_wg_waiting_on);
+loom_test_case!(async test_labeled_wg_with_timeout);
+loom_test_case! {
+    async test_labeled_wg_multiple_threads,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+
+// shutdown
+loom_test_case!(async test_shutdown_closed_once_stopping);
+loom_test_case!(test_shutdown_drop_controller);
+loom_test_case!(test_shutdown_trigger_and_drain);
+loom_test_case! {
+    async test_shutdown_in_another_thread,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+
+// tracker
+loom_test_case!(test_tracker_close_empty);
+loom_test_case!(test_tracker_reopen);
+loom_test_case!(test_tracker_track_after_done);
+loom_test_case!(test_tracker_track_and_close);
+loom_test_case!(test_tracker_wait_after_track_after_close);
+loom_test_case!(async test_tracker_track_in_another_thread);
+
+// stream
+#[cfg(feature = "futures-io")]
+loom_test_case!(test_async_read_release_on_eof);
+#[cfg(feature = "futures-core")]
+loom_test_case!(test_stream_release_on_drop);
+#[cfg(feature = "futures-core")]
+loom_test_case!(test_stream_release_on_end);
+
 // panic
 loom_test_case!(panic test_mono_wg_panic_both);
 loom_test_case!(panic test_mono_wg_panic_handle);
@@ -103,6 +243,27 @@ loom_test_case!(panic test_wg_panic_both);
 loom_test_case!(panic test_wg_panic_handle);
 loom_test_case!(panic test_wg_panic_wg);
 
+// shared
+loom_test_case!(test_shared_wg_done);
+loom_test_case!(test_shared_wg_dedup_waker);
+loom_test_case!(test_shared_wg_send_before_poll);
+loom_test_case!(test_shared_wg_drop_before_send);
+loom_test_case!(test_shared_wg_cancelled_wait_unregisters);
+loom_test_case!(test_shared_wg_wait_wakes_once);
+loom_test_case!(async test_shared_wg_await_in_place);
+loom_test_case! {
+    async test_shared_wg_await_background,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+loom_test_case! {
+    async test_shared_wg_cancel_wait_background,
+    |builder: &mut loom::model::Builder| {
+        builder.preemption_bound = Some(2);
+    }
+}
+
 // twin_ref
 loom_test_case!(test_twin_ref_clonable);
 loom_test_case!(test_twin_ref_mono);
@@ -1,5 +1,8 @@
+mod blocking;
 mod default;
+mod heartbeat;
 mod loom;
 mod panic;
+mod stats;
 mod traits;
 mod utils;
@@ -46,3 +46,60 @@ async fn test_wg_threads_panic() {
     assert!(inspector.load());
     assert!(bg_wg.is_done());
 }
+
+#[futures_test::test]
+#[cfg(all(feature = "std", panic = "unwind"))]
+async fn test_panic_wg_release_on_return() {
+    extern crate std;
+    use crate::{GroupTokenFuncExt, PanicWaitGroup};
+
+    let (wg, factory) = PanicWaitGroup::new();
+    let handles = factory.scope(|token| {
+        [
+            std::thread::spawn((|| 1).release_on_return(token.clone())),
+            std::thread::spawn((|| panic!("boom")).release_on_return(token)),
+        ]
+    });
+    let [ok, panicked] = handles.map(|handle| handle.join().unwrap());
+    assert_eq!(ok, Some(1));
+    assert_eq!(panicked, None);
+
+    let poisoned = wg.await.unwrap_err();
+    assert_eq!(
+        poisoned.payload().and_then(|p| p.downcast_ref::<&str>()),
+        Some(&"boom")
+    );
+    let res = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| poisoned.resume_unwind()));
+    assert_eq!(res.unwrap_err().downcast_ref::<&str>(), Some(&"boom"));
+}
+
+#[futures_test::test]
+#[cfg(all(feature = "std", panic = "unwind"))]
+async fn test_panic_wg_poisoned_on_unwind() {
+    use crate::PanicWaitGroup;
+
+    let (wg, factory) = PanicWaitGroup::new();
+    let token = factory.into_token();
+    async {}.release_on_ready(token.clone()).run_in_background();
+    async {
+        panic!();
+    }
+    .release_on_ready(token)
+    .run_in_background();
+
+    let poisoned = wg.await.unwrap_err();
+    assert!(poisoned.payload().is_none());
+}
+
+#[futures_test::test]
+#[cfg(feature = "std")]
+async fn test_panic_wg_not_poisoned() {
+    use crate::PanicWaitGroup;
+
+    let (mut wg, factory) = PanicWaitGroup::new();
+    let token = factory.into_token();
+    async {}.release_on_ready(token.clone()).run_in_background();
+    token.release();
+    assert!(Pin::new(&mut wg).await.is_ok());
+    assert!(wg.is_done());
+}
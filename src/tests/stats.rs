@@ -0,0 +1,113 @@
+#![cfg(all(feature = "stats", not(loom)))]
+
+use std::{thread, time::Duration};
+
+use crate::{ReusableWaitGroup, TokenStats, WaitGroup};
+
+#[test]
+fn test_wg_stats_peak_outstanding() {
+    let (wg, factory) = WaitGroup::new();
+    let token = factory.into_token();
+    let tokens = [token.clone(), token.clone()];
+    drop(tokens);
+    let token_cloned = token.clone();
+
+    let stats = wg.stats();
+    assert_eq!(stats.created, 4);
+    assert_eq!(stats.released, 2);
+    assert_eq!(stats.outstanding(), 2);
+    assert_eq!(stats.peak_outstanding, 3);
+
+    drop((token, token_cloned));
+    assert!(wg.is_done());
+    let stats = wg.stats();
+    assert_eq!(stats.released, 4);
+    assert_eq!(stats.outstanding(), 0);
+    assert_eq!(stats.peak_outstanding, 3);
+}
+
+#[test]
+fn test_wg_stats_lifetimes() {
+    let (mut wg, factory) = WaitGroup::new();
+    let token = factory.into_token();
+    let token_cloned = token.clone();
+    drop(token_cloned);
+
+    let worker = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        token.release();
+    });
+    wg.wait_blocking();
+    worker.join().unwrap();
+
+    let stats = wg.stats();
+    assert_eq!(stats.lifetimes.iter().sum::<usize>(), 2);
+    assert!(stats.max_lifetime >= Duration::from_millis(20));
+    let bucket = TokenStats::LIFETIME_BOUNDS
+        .iter()
+        .position(|&bound| stats.max_lifetime < bound)
+        .unwrap_or(TokenStats::LIFETIME_BOUNDS.len());
+    assert!(bucket >= 2);
+    assert_eq!(stats.lifetimes[bucket], 1);
+}
+
+#[test]
+fn test_wg_stats_child_and_waker() {
+    let (wg, factory) = WaitGroup::new();
+    let (child_wg, child_factory) = factory.scope(|token| token.child());
+    let waker = child_factory.into_token().into_waker();
+
+    let stats = child_wg.stats();
+    assert_eq!(stats.created, 1);
+    assert_eq!(stats.released, 0);
+    assert!(!child_wg.is_done());
+
+    // The child group holds a token of its parent until it completes.
+    let stats = wg.stats();
+    assert_eq!(stats.created, 2);
+    assert_eq!(stats.released, 1);
+    assert_eq!(stats.peak_outstanding, 2);
+
+    waker.wake();
+    assert_eq!(child_wg.stats().released, 1);
+    assert!(child_wg.is_done());
+    assert!(wg.is_done());
+    assert_eq!(wg.stats().released, 2);
+}
+
+#[test]
+fn test_wg_stats_waker() {
+    let (wg, factory) = WaitGroup::new();
+    let waker = factory.into_token().into_waker();
+    let wakers = [waker.clone(), waker.clone()];
+    drop(wakers);
+    assert!(!wg.is_done());
+
+    // The token is counted until the waker releases it.
+    let stats = wg.stats();
+    assert_eq!(stats.created, 1);
+    assert_eq!(stats.released, 0);
+    assert_eq!(stats.peak_outstanding, 1);
+
+    waker.wake();
+    assert!(wg.is_done());
+    assert_eq!(wg.stats().released, 1);
+}
+
+#[test]
+fn test_reusable_wg_reset_stats() {
+    let (mut wg, factory) = ReusableWaitGroup::new();
+    let token = factory.into_token();
+    drop([token.clone(), token.clone()]);
+    drop(token);
+
+    let factory = wg.reset().unwrap();
+    let stats = wg.stats();
+    assert_eq!(stats.created, 1);
+    assert_eq!(stats.released, 0);
+    assert_eq!(stats.peak_outstanding, 1);
+    assert_eq!(stats.lifetimes.iter().sum::<usize>(), 0);
+
+    factory.release();
+    assert_eq!(wg.stats().released, 1);
+}
@@ -2,15 +2,129 @@
 
 use core::panic::{RefUnwindSafe, UnwindSafe};
 
+use alloc::string::String;
+
 use static_assertions::{assert_impl_all, assert_not_impl_any};
 
-use crate::{GroupToken, MonoGroupToken, MonoWaitGroup, WaitGroup};
+use crate::{
+    Abandoned, BoundedGroupToken, BoundedGroupTokenFactory, BoundedWaitGroup, CounterWaitGroup,
+    ErrorGroupToken, ErrorWaitGroup, Gate, GroupToken, GroupTokenAbortOnAbandon, GroupTokenType,
+    LabeledGroupToken, LabeledGroupTokenFactory, LabeledWaitGroup, MonoGroupToken,
+    MonoValueGroupToken, MonoValueWaitGroup, MonoWaitGroup, SharedGroupToken, SharedWaitGroup,
+    Shutdown, ShutdownToken, StartupGroupTokenFactory, TaskTracker, TaskTrackerToken, TimedOut,
+    WaitAbandoned, WaitGroup, WaitShared, WaitTracker, WaitingOn, WithTimeout,
+};
 
 assert_impl_all!(WaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe);
 assert_impl_all!(GroupToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
 assert_impl_all!(MonoWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe);
 assert_impl_all!(MonoGroupToken: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(SharedWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+assert_impl_all!(SharedGroupToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+assert_impl_all!(StartupGroupTokenFactory: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(LabeledWaitGroup<String>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(LabeledGroupTokenFactory<String>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(LabeledGroupToken<String>: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+assert_impl_all!(WaitingOn<String>: Sync, Send, Clone);
+assert_impl_all!(WithTimeout<WaitGroup, MonoWaitGroup>: Sync, Send, Unpin);
+assert_impl_all!(WaitShared<'static>: Sync, Send, UnwindSafe, RefUnwindSafe, Unpin);
+assert_impl_all!(TimedOut<WaitGroup>: Sync, Send, core::error::Error);
+
+assert_impl_all!(CounterWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe, Default);
+
+assert_impl_all!(Gate: Sync, Send, UnwindSafe, RefUnwindSafe, Default);
+
+assert_impl_all!(BoundedWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(BoundedGroupTokenFactory: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(BoundedGroupToken: Sync, Send, UnwindSafe, RefUnwindSafe);
+
+assert_impl_all!(Shutdown: Sync, Send, UnwindSafe, RefUnwindSafe, Default);
+assert_impl_all!(ShutdownToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+
+assert_impl_all!(TaskTracker: Sync, Send, UnwindSafe, RefUnwindSafe, Default);
+assert_impl_all!(TaskTrackerToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+assert_impl_all!(WaitTracker: Sync, Send, UnwindSafe, RefUnwindSafe, Unpin);
+
+assert_impl_all!(ErrorWaitGroup<u8>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(ErrorGroupToken<u8>: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+
+assert_impl_all!(MonoValueWaitGroup<u8>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(MonoValueGroupToken<u8>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_not_impl_any!(MonoValueWaitGroup<alloc::rc::Rc<u8>>: Sync, Send);
+
+assert_impl_all!(MonoValueGroupToken<u8>: GroupTokenType);
+assert_impl_all!(BoundedGroupTokenFactory: GroupTokenType);
+assert_impl_all!(StartupGroupTokenFactory: GroupTokenType);
+assert_impl_all!((GroupToken, Option<SharedGroupToken>): GroupTokenType);
+assert_not_impl_any!(ErrorGroupToken<u8>: GroupTokenType);
 
 assert_not_impl_any!(WaitGroup: Clone);
+assert_not_impl_any!(MonoValueGroupToken<u8>: Clone);
+assert_not_impl_any!(ErrorWaitGroup<u8>: Clone);
+assert_not_impl_any!(Gate: Clone);
+assert_not_impl_any!(Shutdown: Clone);
+assert_not_impl_any!(BoundedGroupToken: Clone);
+assert_not_impl_any!(BoundedGroupTokenFactory: Clone);
+assert_not_impl_any!(TaskTracker: Clone);
+assert_not_impl_any!(CounterWaitGroup: Clone);
 assert_not_impl_any!(MonoGroupToken: Clone);
 assert_not_impl_any!(MonoWaitGroup: Clone);
+
+#[test]
+fn test_debug_outstanding() {
+    extern crate std;
+    use std::format;
+
+    let (wg, factory) = WaitGroup::new();
+    let token = factory.into_token();
+    let _token_cloned = token.clone();
+    assert_eq!(format!("{wg:?}"), "WaitGroup(done: false, outstanding: 2)");
+    assert_eq!(
+        format!("{token:?}"),
+        "GroupToken(done: false, outstanding: 2)"
+    );
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use crate::{PanicGroupToken, PanicWaitGroup};
+
+    assert_impl_all!(PanicWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe);
+    assert_impl_all!(PanicGroupToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+    assert_not_impl_any!(PanicWaitGroup: Clone);
+};
+
+#[cfg(feature = "std")]
+const _: () = {
+    use crate::{HeartbeatGroupToken, HeartbeatGroupTokenFactory, HeartbeatWaitGroup};
+
+    assert_impl_all!(HeartbeatWaitGroup: Sync, Send, UnwindSafe, RefUnwindSafe);
+    assert_impl_all!(HeartbeatGroupTokenFactory: Sync, Send, UnwindSafe, RefUnwindSafe);
+    assert_impl_all!(HeartbeatGroupToken: Sync, Send, UnwindSafe, RefUnwindSafe, Clone);
+};
+
+#[cfg(feature = "stats")]
+const _: () = {
+    use crate::TokenStats;
+
+    assert_impl_all!(TokenStats: Sync, Send, UnwindSafe, RefUnwindSafe, Copy);
+};
+
+assert_impl_all!(WaitAbandoned<'static, GroupToken>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(WaitAbandoned<'static, MonoGroupToken>: Sync, Send, UnwindSafe, RefUnwindSafe);
+assert_impl_all!(Abandoned: Sync, Send, Copy, core::error::Error);
+assert_impl_all!(GroupTokenAbortOnAbandon<core::future::Ready<u8>, GroupToken>: Sync, Send, Unpin);
+
+fn assert_send<T: Send>(_: &T) {}
+
+#[test]
+fn test_closure_ext_send() {
+    use crate::{GroupTokenAsyncFuncExt, GroupTokenFuncExt};
+
+    let (_wg, factory) = WaitGroup::new();
+    let token = factory.into_token();
+    let func = (|| 42).release_on_return(token.clone());
+    assert_send(&func);
+    let fut = (async || 42).release_on_complete(token);
+    assert_send(&fut);
+}
@@ -1,3 +1,5 @@
+use alloc::task::Wake;
+
 use crate::utils::*;
 
 #[cfg(not(loom))]
@@ -25,3 +27,10 @@ impl SharedData {
         self.0.store(1, atomic::Release);
     }
 }
+
+/// Waker doing nothing, whose clones are counted by its `Arc`.
+pub(super) struct NoopWake;
+
+impl Wake for NoopWake {
+    fn wake(self: alloc::sync::Arc<Self>) {}
+}
@@ -0,0 +1,247 @@
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    ext::{GroupTokenExt, GroupTokenReleaseOnReady},
+    group::{SharedGroupToken, SharedWaitGroup},
+    sync::SpinLock,
+};
+
+/// Current round of a [`TaskTracker`].
+///
+/// The round completes once the tracker is closed and no token is alive. A
+/// future tracked after that starts a new round.
+struct Round {
+    wg: SharedWaitGroup,
+    /// Token held while the tracker is open.
+    open: Option<SharedGroupToken>,
+}
+
+impl Round {
+    #[inline]
+    fn new() -> Self {
+        let (wg, factory) = SharedWaitGroup::new();
+        Self {
+            wg,
+            open: Some(factory.into_token()),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.wg.outstanding() - usize::from(self.open.is_some())
+    }
+
+    /// Creates a token of the current round, or starts a new round if the
+    /// current one has completed.
+    ///
+    /// Returns the group of the completed round, to be dropped outside the
+    /// lock.
+    fn token(&mut self) -> (SharedGroupToken, Option<SharedWaitGroup>) {
+        if let Some(token) = &self.open {
+            return (token.clone(), None);
+        }
+        if let Some(token) = self.wg.try_token() {
+            return (token, None);
+        }
+        let (wg, factory) = SharedWaitGroup::new();
+        (
+            factory.into_token(),
+            Some(core::mem::replace(&mut self.wg, wg)),
+        )
+    }
+}
+
+/// Tracker of futures, in the style of `tokio_util::task::TaskTracker`.
+///
+/// Every future passed to [`track_future`](Self::track_future) holds a
+/// [`TaskTrackerToken`] until it is ready or dropped. Waiting resolves once
+/// the tracker is [closed](Self::close) and no token is alive.
+///
+/// Closing only affects waiting: futures can still be tracked after the
+/// tracker is closed. A tracker that is done stops being done once a new
+/// future is tracked, and becomes done again once that future is. The future
+/// returned by [`wait`](Self::wait) resolves the first time the tracker is
+/// done after it is created, even if a future is tracked again afterwards.
+/// The tracker works with any runtime.
+///
+/// The tracker is built on a [`SharedWaitGroup`], holding one of its tokens
+/// while open.
+///
+/// # Cancellation safety
+///
+/// The future returned by [`wait`](Self::wait) is cancellation safe.
+///
+/// ```rust
+/// # use compact_waitgroup::TaskTracker;
+/// # futures_executor::block_on(async {
+/// let tracker = TaskTracker::new();
+///
+/// let task = tracker.track_future(async { 42 });
+/// assert_eq!(tracker.len(), 1);
+///
+/// tracker.close();
+/// assert_eq!(task.await, 42);
+///
+/// tracker.wait().await;
+/// assert!(tracker.is_empty());
+/// # });
+/// ```
+#[must_use]
+pub struct TaskTracker(SpinLock<Round>);
+
+/// Token of a [`TaskTracker`].
+///
+/// Keeps the [`TaskTracker`] from completing until released, even if the
+/// tracker itself is dropped first.
+#[must_use]
+#[derive(Clone)]
+pub struct TaskTrackerToken(#[allow(unused)] SharedGroupToken);
+
+/// Future returned by [`TaskTracker::wait`].
+///
+/// Resolves once the tracker is closed and no token is alive. Its waker is
+/// unregistered once it is dropped.
+///
+/// # Cancellation safety
+///
+/// This future is cancellation safe.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct WaitTracker(SharedWaitGroup);
+
+impl TaskTracker {
+    /// Creates a new open `TaskTracker`.
+    #[inline]
+    pub fn new() -> Self {
+        Self(SpinLock::new(Round::new()))
+    }
+
+    /// Tracks a future until it is ready or dropped.
+    #[inline]
+    pub fn track_future<F: Future>(&self, fut: F) -> GroupTokenReleaseOnReady<F, TaskTrackerToken> {
+        fut.release_on_ready(self.token())
+    }
+
+    /// Returns a [`TaskTrackerToken`] that keeps the tracker from completing
+    /// until released.
+    #[inline]
+    pub fn token(&self) -> TaskTrackerToken {
+        let (token, _done) = self.0.with(Round::token);
+        TaskTrackerToken(token)
+    }
+
+    /// Closes the tracker, so that it completes once no token is alive.
+    ///
+    /// Returns `true` if the tracker was open. Closing an already closed
+    /// tracker does nothing.
+    pub fn close(&self) -> bool {
+        // Dropping the last token wakes the waiters, so it is done outside the
+        // lock.
+        let open = self.0.with(|round| round.open.take());
+        open.is_some()
+    }
+
+    /// Reopens the tracker, so that it does not complete until closed again.
+    ///
+    /// Returns `true` if the tracker was closed.
+    pub fn reopen(&self) -> bool {
+        let (was_closed, _done) = self.0.with(|round| {
+            if round.open.is_some() {
+                return (false, None);
+            }
+            let (token, done) = round.token();
+            round.open = Some(token);
+            (true, done)
+        });
+        was_closed
+    }
+
+    /// Checks if the `TaskTracker` has been closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.0.with(|round| round.open.is_none())
+    }
+
+    /// Returns the number of tracked futures and tokens still alive.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.with(|round| round.len())
+    }
+
+    /// Checks if no tracked future or token is alive.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the `TaskTracker` has completed.
+    ///
+    /// This returns `true` if the tracker is closed and empty.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.with(|round| round.wg.is_done())
+    }
+
+    /// Polls the `TaskTracker` for completion through a shared reference.
+    ///
+    /// Registers the waker as [`SharedWaitGroup::poll_done`] does.
+    pub fn poll_done(&self, cx: &mut Context<'_>) -> Poll<()> {
+        // The waker is registered outside the lock, as the group wakes the
+        // registered wakers if it completes meanwhile.
+        let wg = self.0.with(|round| round.wg.clone());
+        wg.poll_done(cx)
+    }
+
+    /// Returns a future that waits for the `TaskTracker` to be closed and
+    /// empty.
+    #[inline]
+    pub fn wait(&self) -> WaitTracker {
+        WaitTracker(self.0.with(|round| round.wg.clone()))
+    }
+}
+
+impl TaskTrackerToken {
+    /// Consumes the token.
+    ///
+    /// This is equivalent to dropping the token.
+    #[inline]
+    pub fn release(self) {
+        drop(self);
+    }
+}
+
+impl Default for TaskTracker {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Future for WaitTracker {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.get_mut().0).poll(cx)
+    }
+}
+
+impl fmt::Debug for TaskTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (closed, len) = self.0.with(|round| (round.open.is_none(), round.len()));
+        f.debug_struct("TaskTracker")
+            .field("closed", &closed)
+            .field("len", &len)
+            .finish()
+    }
+}
+
+impl fmt::Debug for TaskTrackerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskTrackerToken").finish_non_exhaustive()
+    }
+}
@@ -3,6 +3,8 @@
 use core::{
     borrow::Borrow,
     fmt::Debug,
+    marker::PhantomData,
+    mem::ManuallyDrop,
     ops::Deref,
     panic::{RefUnwindSafe, UnwindSafe},
     ptr::NonNull,
@@ -19,6 +21,20 @@ use crate::utils::*;
 ///   initialized to `2`.
 pub(crate) unsafe trait TwinRefLayout {
     fn count(&self) -> &AtomicU8;
+
+    /// Deallocates the data once both twins have been dropped.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be the pointer of the allocation, and no reference to the
+    /// data may be used afterwards.
+    #[inline]
+    unsafe fn dealloc(this: NonNull<Self>)
+    where
+        Self: Sized,
+    {
+        drop(unsafe { Box::from_raw(this.as_ptr()) });
+    }
 }
 
 /// # Safety
@@ -27,9 +43,25 @@ pub(crate) unsafe trait TwinRefLayout {
 ///   `ClonableTwinRefType`, and initialized to `1`.
 /// - `action_on_zero` will be called only once just after `cloned_count`
 ///   reaches zero.
-pub(crate) unsafe trait ClonableTwinRefLayout {
+/// - `S` marks the side of the twin references. Each side must use its own
+///   `cloned_count`.
+pub(crate) unsafe trait ClonableTwinRefLayout<S = ()> {
     fn cloned_count(&self) -> &AtomicUsize;
     fn action_on_zero(&self);
+
+    /// Same as `action_on_zero`, with the pointer of the allocation.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be the pointer of the allocation, which must stay alive
+    /// during the call.
+    #[inline]
+    unsafe fn action_on_zero_raw(this: NonNull<Self>)
+    where
+        Self: Sized,
+    {
+        unsafe { this.as_ref() }.action_on_zero();
+    }
 }
 
 // ThreadSanitizer does not support memory fences. To avoid false positive
@@ -95,8 +127,7 @@ impl<T: TwinRefLayout> TwinRefPtr<T> {
             return;
         }
         acquire!(self.count());
-        let raw = self.0.as_ptr();
-        let _ = unsafe { Box::from_raw(raw) };
+        unsafe { T::dealloc(self.0) };
     }
 }
 
@@ -104,7 +135,10 @@ impl<T: TwinRefLayout> TwinRefPtr<T> {
 pub(crate) struct TwinRef<T: TwinRefLayout>(TwinRefPtr<T>);
 
 #[derive(Debug, Deref)]
-pub(crate) struct ClonableTwinRef<T: TwinRefLayout + ClonableTwinRefLayout>(TwinRefPtr<T>);
+pub(crate) struct ClonableTwinRef<T: TwinRefLayout + ClonableTwinRefLayout<S>, S = ()>(
+    #[deref] TwinRefPtr<T>,
+    PhantomData<S>,
+);
 
 impl<T: TwinRefLayout> TwinRef<T> {
     #[must_use]
@@ -115,12 +149,156 @@ impl<T: TwinRefLayout> TwinRef<T> {
     }
 }
 
+impl<T: TwinRefLayout> TwinRef<T> {
+    /// Consumes the twin without dropping it, and returns its pointer.
+    #[must_use]
+    #[inline]
+    pub fn into_raw(self) -> NonNull<T> {
+        ManuallyDrop::new(self).0.0
+    }
+
+    /// Re-creates a twin from the pointer returned by [`TwinRef::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be returned by [`TwinRef::into_raw`] of the same type, and
+    /// must be re-created only once.
+    #[must_use]
+    #[inline]
+    pub unsafe fn from_raw(ptr: NonNull<T>) -> Self {
+        Self(TwinRefPtr(ptr))
+    }
+}
+
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> ClonableTwinRef<T, S> {
+    /// Creates a twin of the side `R`, unless every twin of that side has
+    /// been dropped.
+    ///
+    /// Adapted from `alloc::sync::Weak::upgrade`.
+    #[must_use]
+    #[inline]
+    pub fn try_upgrade<R>(&self) -> Option<ClonableTwinRef<T, R>>
+    where
+        T: ClonableTwinRefLayout<R>,
+    {
+        let layout: &T = self;
+        ClonableTwinRefLayout::<R>::cloned_count(layout)
+            .fetch_update(atomic::Acquire, atomic::Relaxed, |count| {
+                if count == 0 {
+                    return None;
+                }
+                if count > usize::MAX / 2 {
+                    panic!("reference count overflow");
+                }
+                Some(count + 1)
+            })
+            .ok()?;
+        // The side `R` keeps its twin alive as long as its count is nonzero.
+        Some(ClonableTwinRef(unsafe { self.dup() }, PhantomData))
+    }
+}
+
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> ClonableTwinRef<T, S> {
+    /// Counts `n` more clones, to be taken over by twins created with
+    /// [`ClonableTwinRef::dup_counted`].
+    #[inline]
+    pub fn count_many(&self, n: usize) {
+        let layout: &T = self;
+        let old_size =
+            ClonableTwinRefLayout::<S>::cloned_count(layout).fetch_add(n, atomic::Relaxed);
+
+        if old_size > usize::MAX / 2 || n > usize::MAX / 2 {
+            panic!("reference count overflow");
+        }
+    }
+
+    /// Creates a twin standing for clones already counted, which is not
+    /// released when dropped.
+    ///
+    /// # Safety
+    ///
+    /// The clones must be counted by [`ClonableTwinRef::count_many`], and
+    /// released by [`ClonableTwinRef::release_many`] of the returned twin.
+    #[inline]
+    pub unsafe fn dup_counted(&self) -> ManuallyDrop<Self> {
+        ManuallyDrop::new(Self(unsafe { self.dup() }, PhantomData))
+    }
+
+    /// Releases `n` clones counted for this twin.
+    ///
+    /// Returns the last twin if no clone is left, which runs `action_on_zero`
+    /// and drops the twin once dropped.
+    ///
+    /// # Safety
+    ///
+    /// `n` clones must be counted for this twin, which must not be used
+    /// afterwards.
+    #[inline]
+    pub unsafe fn release_many(&self, n: usize) -> Option<LastTwin<T, S>> {
+        let layout: &T = self;
+        if ClonableTwinRefLayout::<S>::cloned_count(layout).fetch_sub(n, atomic::Release) != n {
+            return None;
+        }
+        acquire!(ClonableTwinRefLayout::<S>::cloned_count(layout));
+        Some(LastTwin(unsafe { self.dup() }, PhantomData))
+    }
+}
+
 impl<T: TwinRefLayout + ClonableTwinRefLayout> TwinRef<T> {
     #[must_use]
     #[inline]
     pub fn new_clonable(data: T) -> (Self, ClonableTwinRef<T>) {
         let ptr = unsafe { TwinRefPtr::new(data) };
-        (Self(unsafe { ptr.dup() }), ClonableTwinRef(ptr))
+        (
+            Self(unsafe { ptr.dup() }),
+            ClonableTwinRef(ptr, PhantomData),
+        )
+    }
+}
+
+impl<T: TwinRefLayout + ClonableTwinRefLayout> TwinRef<T> {
+    /// Same as [`TwinRef::new_clonable`], but allocates `data` as a `U`.
+    ///
+    /// # Safety
+    ///
+    /// `U` must be `repr(C)` with a `T` as its first field, and
+    /// [`TwinRefLayout::dealloc`] of `T` must deallocate it as a `U`.
+    #[must_use]
+    #[inline]
+    pub unsafe fn new_clonable_in<U>(data: U) -> (Self, ClonableTwinRef<T>) {
+        let ptr = TwinRefPtr(NonNull::from(Box::leak(Box::new(data))).cast::<T>());
+        (
+            Self(unsafe { ptr.dup() }),
+            ClonableTwinRef(ptr, PhantomData),
+        )
+    }
+
+    /// Re-creates the clonable twin, once the previous one has been dropped.
+    ///
+    /// `reset` is called with exclusive access to the data before the twin is
+    /// created.
+    #[must_use]
+    #[inline]
+    pub fn try_rearm_clonable<F: FnOnce(&T)>(&mut self, reset: F) -> Option<ClonableTwinRef<T>> {
+        if self.count().load(atomic::Acquire) != 1 {
+            return None;
+        }
+        reset(self);
+        self.cloned_count().store(1, atomic::Relaxed);
+        self.count().store(2, atomic::Release);
+        Some(ClonableTwinRef(unsafe { self.0.dup() }, PhantomData))
+    }
+}
+
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S> + ClonableTwinRefLayout, S> ClonableTwinRef<T, S> {
+    #[must_use]
+    #[inline]
+    pub fn new_twin_clonable(data: T) -> (Self, ClonableTwinRef<T>) {
+        let ptr = unsafe { TwinRefPtr::new(data) };
+        (
+            Self(unsafe { ptr.dup() }, PhantomData),
+            ClonableTwinRef(ptr, PhantomData),
+        )
     }
 }
 
@@ -134,9 +312,9 @@ impl<T: TwinRefLayout> Drop for TwinRef<T> {
 }
 
 #[must_use]
-struct DropGuard<T: TwinRefLayout + ClonableTwinRefLayout>(TwinRefPtr<T>);
+struct DropGuard<T: TwinRefLayout>(TwinRefPtr<T>);
 
-impl<T: TwinRefLayout + ClonableTwinRefLayout> Drop for DropGuard<T> {
+impl<T: TwinRefLayout> Drop for DropGuard<T> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
@@ -145,19 +323,37 @@ impl<T: TwinRefLayout + ClonableTwinRefLayout> Drop for DropGuard<T> {
     }
 }
 
-impl<T: TwinRefLayout + ClonableTwinRefLayout> Drop for ClonableTwinRef<T> {
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> Drop for ClonableTwinRef<T, S> {
     #[inline]
     fn drop(&mut self) {
-        if self.cloned_count().fetch_sub(1, atomic::Release) != 1 {
+        let layout: &T = self;
+        if ClonableTwinRefLayout::<S>::cloned_count(layout).fetch_sub(1, atomic::Release) != 1 {
             return;
         }
-        acquire!(self.cloned_count());
+        acquire!(ClonableTwinRefLayout::<S>::cloned_count(layout));
         let _guard = DropGuard(unsafe { self.dup() });
-        self.action_on_zero();
+        unsafe { <T as ClonableTwinRefLayout<S>>::action_on_zero_raw(self.0.0) };
+    }
+}
+
+/// Last clone released by [`ClonableTwinRef::release_many`].
+///
+/// Runs `action_on_zero` and drops the twin once dropped.
+#[must_use]
+pub(crate) struct LastTwin<T: TwinRefLayout + ClonableTwinRefLayout<S>, S = ()>(
+    TwinRefPtr<T>,
+    PhantomData<S>,
+);
+
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> Drop for LastTwin<T, S> {
+    #[inline]
+    fn drop(&mut self) {
+        let _guard = DropGuard(unsafe { self.0.dup() });
+        unsafe { <T as ClonableTwinRefLayout<S>>::action_on_zero_raw(self.0.0) };
     }
 }
 
-impl<T: TwinRefLayout + ClonableTwinRefLayout> Clone for ClonableTwinRef<T> {
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> Clone for ClonableTwinRef<T, S> {
     #[inline]
     fn clone(&self) -> Self {
         // Using a relaxed ordering is alright here, as knowledge of the
@@ -171,13 +367,15 @@ impl<T: TwinRefLayout + ClonableTwinRefLayout> Clone for ClonableTwinRef<T> {
         // another must already provide any required synchronization.
         //
         // [1]: (www.boost.org/doc/libs/1_55_0/doc/html/atomic/usage_examples.html)
-        let old_size = self.cloned_count().fetch_add(1, atomic::Relaxed);
+        let layout: &T = self;
+        let old_size =
+            ClonableTwinRefLayout::<S>::cloned_count(layout).fetch_add(1, atomic::Relaxed);
 
         if old_size > usize::MAX / 2 {
             panic!("reference count overflow");
         }
 
-        Self(unsafe { self.dup() })
+        Self(unsafe { self.dup() }, PhantomData)
     }
 }
 
@@ -188,7 +386,7 @@ impl<T: TwinRefLayout> Borrow<T> for TwinRef<T> {
     }
 }
 
-impl<T: TwinRefLayout + ClonableTwinRefLayout> Borrow<T> for ClonableTwinRef<T> {
+impl<T: TwinRefLayout + ClonableTwinRefLayout<S>, S> Borrow<T> for ClonableTwinRef<T, S> {
     #[inline]
     fn borrow(&self) -> &T {
         self
@@ -1,9 +1,16 @@
 #[cfg(all(not(loom), not(feature = "portable-atomic")))]
-pub use core::sync::atomic::{self as _atomic, AtomicU8, AtomicUsize};
+pub use core::sync::atomic::{self as _atomic, AtomicU8, AtomicU32, AtomicUsize};
 #[cfg(loom)]
-pub use loom::sync::atomic::{self as _atomic, AtomicU8, AtomicUsize};
+pub use loom::sync::atomic::{self as _atomic, AtomicU8, AtomicU32, AtomicUsize};
 #[cfg(all(not(loom), feature = "portable-atomic"))]
-pub use portable_atomic::{self as _atomic, AtomicU8, AtomicUsize};
+pub use portable_atomic::{self as _atomic, AtomicU8, AtomicU32, AtomicUsize};
+
+#[cfg(all(feature = "stats", not(loom), not(feature = "portable-atomic")))]
+pub use core::sync::atomic::AtomicU64;
+#[cfg(all(feature = "stats", loom))]
+pub use loom::sync::atomic::AtomicU64;
+#[cfg(all(feature = "stats", not(loom), feature = "portable-atomic"))]
+pub use portable_atomic::AtomicU64;
 
 pub mod atomic {
     pub use super::_atomic::{Ordering::*, fence};
@@ -13,3 +20,8 @@ pub mod atomic {
 pub use core::cell::UnsafeCell;
 #[cfg(loom)]
 pub use loom::cell::UnsafeCell;
+
+#[cfg(not(loom))]
+pub use core::hint::spin_loop;
+#[cfg(loom)]
+pub use loom::thread::yield_now as spin_loop;
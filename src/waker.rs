@@ -0,0 +1,163 @@
+use core::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ptr::NonNull,
+    task::{RawWaker, RawWakerVTable, Waker},
+};
+
+use alloc::boxed::Box;
+
+use crate::{
+    group::GroupToken,
+    layout::MonoLayout,
+    sync::{SpinLock, WaitGroupLayoutExt},
+    twin_ref::{TwinRef, TwinRefLayout},
+    utils::*,
+};
+
+/// Creates a waker owning a group token, released by the first wake.
+///
+/// The clones of the waker share the token in a small allocation, which is
+/// freed once every clone is dropped. The token is also released then, if no
+/// clone has been woken.
+#[inline]
+pub(crate) fn token_waker(token: GroupToken) -> Waker {
+    let data = Box::new(TokenWaker {
+        clones: AtomicUsize::new(1),
+        token: SpinLock::new(Some(token)),
+    });
+    unsafe { Waker::from_raw(token_raw_waker(Box::into_raw(data))) }
+}
+
+struct TokenWaker {
+    /// Number of clones of the waker.
+    clones: AtomicUsize,
+    /// Token taken by the first wake.
+    token: SpinLock<Option<GroupToken>>,
+}
+
+const TOKEN_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(token_clone, token_wake, token_wake_by_ref, token_drop);
+
+#[inline]
+fn token_raw_waker(data: *const TokenWaker) -> RawWaker {
+    RawWaker::new(data.cast(), &TOKEN_VTABLE)
+}
+
+#[inline]
+unsafe fn token_waker_ref<'a>(data: *const ()) -> &'a TokenWaker {
+    unsafe { &*data.cast::<TokenWaker>() }
+}
+
+unsafe fn token_clone(data: *const ()) -> RawWaker {
+    let old_count = unsafe { token_waker_ref(data) }
+        .clones
+        .fetch_add(1, atomic::Relaxed);
+    if old_count > usize::MAX / 2 {
+        panic!("waker count overflow");
+    }
+    token_raw_waker(data.cast())
+}
+
+unsafe fn token_wake(data: *const ()) {
+    unsafe {
+        token_wake_by_ref(data);
+        token_drop(data);
+    }
+}
+
+unsafe fn token_wake_by_ref(data: *const ()) {
+    let token = unsafe { token_waker_ref(data) }.token.with(Option::take);
+    drop(token);
+}
+
+unsafe fn token_drop(data: *const ()) {
+    let clones = &unsafe { token_waker_ref(data) }.clones;
+    if clones.fetch_sub(1, atomic::Release) != 1 {
+        return;
+    }
+    atomic::fence(atomic::Acquire);
+    drop(unsafe { Box::from_raw(data.cast::<TokenWaker>().cast_mut()) });
+}
+
+/// Creates a waker owning the token side of a mono twin.
+///
+/// The clones of the waker share the token side, counted by the waker count
+/// of the layout. The first wake sends done, and the token side is dropped
+/// once the last clone is dropped, sending done if no clone has been woken.
+///
+/// The count saturates instead of overflowing, leaking the layout.
+#[inline]
+pub(crate) fn mono_waker<T: TwinRefLayout + Borrow<MonoLayout>>(token: TwinRef<T>) -> Waker {
+    let data = token.into_raw().as_ptr().cast();
+    // The token side is unique, so no other waker shares the count.
+    unsafe { MonoWaker::<T>::waker_count(data) }.store(1, atomic::Relaxed);
+    unsafe { Waker::from_raw(RawWaker::new(data, &MonoWaker::<T>::VTABLE)) }
+}
+
+struct MonoWaker<T>(PhantomData<T>);
+
+/// Set in the waker count once a clone has been woken.
+const WOKEN: u32 = 1 << 31;
+/// Mask of the number of clones in the waker count.
+const CLONES: u32 = WOKEN - 1;
+
+impl<T: TwinRefLayout + Borrow<MonoLayout>> MonoWaker<T> {
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake_by_ref, Self::drop);
+
+    #[inline]
+    unsafe fn layout<'a>(data: *const ()) -> &'a T {
+        unsafe { &*data.cast::<T>() }
+    }
+
+    #[inline]
+    unsafe fn waker_count<'a>(data: *const ()) -> &'a AtomicU32
+    where
+        T: 'a,
+    {
+        Borrow::<MonoLayout>::borrow(unsafe { Self::layout(data) }).waker_count()
+    }
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        // A saturated count is never decremented.
+        let _ = unsafe { Self::waker_count(data) }.fetch_update(
+            atomic::Relaxed,
+            atomic::Relaxed,
+            |count| (count & CLONES != CLONES).then_some(count + 1),
+        );
+        RawWaker::new(data, &Self::VTABLE)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        unsafe {
+            Self::wake_by_ref(data);
+            Self::drop(data);
+        }
+    }
+
+    unsafe fn wake_by_ref(data: *const ()) {
+        let count = unsafe { Self::waker_count(data) };
+        if count.fetch_or(WOKEN, atomic::Relaxed) & WOKEN == 0 {
+            unsafe { Self::layout(data).send_done() };
+        }
+    }
+
+    unsafe fn drop(data: *const ()) {
+        let count = unsafe { Self::waker_count(data) };
+        let Ok(prev_count) = count.fetch_update(atomic::Release, atomic::Relaxed, |count| {
+            (count & CLONES != CLONES).then(|| count - 1)
+        }) else {
+            return;
+        };
+        if prev_count & CLONES != 1 {
+            return;
+        }
+        atomic::fence(atomic::Acquire);
+        let token =
+            unsafe { TwinRef::<T>::from_raw(NonNull::new_unchecked(data.cast_mut().cast())) };
+        if prev_count & WOKEN == 0 {
+            unsafe { token.send_done() };
+        }
+    }
+}